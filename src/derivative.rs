@@ -0,0 +1,234 @@
+//! Partial derivatives of equations, uncertainty propagation and sensitivity.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Propagation_of_uncertainty>
+//! - <https://en.wikipedia.org/wiki/Elasticity_of_a_function>
+//!
+//! Analytic derivatives are used when equation provides them,
+//! otherwise central finite differences.
+use super::{Equation, EQUATIONS};
+
+/// Derivatives of outputs with respect to inputs, row-major `nr_out x nr_inp`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{input_jacobian, EquationMaker};
+/// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+/// let mut eq = DistanceVelocityEquation::make(&[2.0, 3.0]);
+/// // ∂s/∂t = v0 + a*t, ∂v/∂t = a
+/// assert_eq!(input_jacobian(eq.as_mut(), &[10.0]), vec![32.0, 3.0]);
+/// ```
+pub fn input_jacobian(equation: &mut dyn Equation, inp: &[f64]) -> Vec<f64> {
+    if let Some(jac) = equation.input_derivatives(inp) {
+        return jac;
+    }
+    central_difference(inp, |x| equation.run(x))
+}
+
+/// Derivatives of outputs with respect to constants, row-major `nr_out x nr_cns`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{constant_jacobian, get_equation_by_typeid, EquationMaker};
+/// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+/// let id = get_equation_by_typeid(VelocityEquation::params).unwrap();
+/// // ∂v/∂v0 = 1, ∂v/∂a = t
+/// assert_eq!(constant_jacobian(id, &[2.0, 3.0], &[10.0]), vec![1.0, 10.0]);
+/// ```
+pub fn constant_jacobian(id: usize, cns: &[f64], inp: &[f64]) -> Vec<f64> {
+    let eq = &EQUATIONS[id];
+    if let Some(jac) = (eq.new)(cns).constant_derivatives(inp) {
+        return jac;
+    }
+    central_difference(cns, |c| (eq.new)(c).run(inp))
+}
+
+/// Standard deviation of every output from independent input uncertainties,
+/// first order `σ(f)² = ∑(∂f/∂xᵢ σᵢ)²`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{propagate_uncertainty, EquationMaker};
+/// use rustamath_physics::figure::rectangle::SquareArea;
+/// let mut eq = SquareArea::make(&[]);
+/// // A = s^2, σ(A) = 2*s*σ(s)
+/// assert_eq!(propagate_uncertainty(eq.as_mut(), &[3.0], &[0.1]), vec![2.0 * 3.0 * 0.1]);
+/// ```
+pub fn propagate_uncertainty(equation: &mut dyn Equation, inp: &[f64], sigmas: &[f64]) -> Vec<f64> {
+    input_jacobian(equation, inp).chunks(inp.len().max(1))
+        .map(|row| row.iter().zip(sigmas.iter()).map(|(d, s)| (d * s) * (d * s)).sum::<f64>().sqrt())
+        .collect()
+}
+
+/// Standard deviation of every output from covariance of constants,
+/// e.g. of [FitResult](crate::FitResult), `σ(f)² = J C Jᵀ`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{propagate_covariance, get_equation_by_typeid, EquationMaker};
+/// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+/// let id = get_equation_by_typeid(VelocityEquation::params).unwrap();
+/// // v = v0 + a*t with independent σ(v0) = 0.3, σ(a) = 0.04 at t = 10
+/// let sigma = propagate_covariance(id, &[2.0, 3.0], &[0.09, 0.0, 0.0, 0.0016], &[10.0]);
+/// assert!((sigma[0] - 0.5).abs() < 1.0e-12);
+/// ```
+pub fn propagate_covariance(id: usize, cns: &[f64], covariance: &[f64], inp: &[f64]) -> Vec<f64> {
+    let nr_cns = cns.len();
+    if nr_cns == 0 {
+        return vec![0.0; (EQUATIONS[id].params)().0.len()];
+    }
+    constant_jacobian(id, cns, inp).chunks(nr_cns)
+        .map(|row| {
+            let mut variance = 0.0_f64;
+            for (j, dj) in row.iter().enumerate() {
+                for (l, dl) in row.iter().enumerate() {
+                    variance += dj * covariance[j * nr_cns + l] * dl;
+                }
+            }
+            variance.sqrt()
+        })
+        .collect()
+}
+
+/// Relative sensitivity `(∂f/∂xᵢ)·xᵢ/f` of outputs to inputs, row-major `nr_out x nr_inp`.
+///
+/// Percent change of output per one percent change of input.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{sensitivity, EquationMaker};
+/// use rustamath_physics::figure::circle::CircleArea;
+/// let mut eq = CircleArea::make(&[]);
+/// // A ~ r^2
+/// assert!((sensitivity(eq.as_mut(), &[3.0])[0] - 2.0).abs() < 1.0e-12);
+/// ```
+pub fn sensitivity(equation: &mut dyn Equation, inp: &[f64]) -> Vec<f64> {
+    let outputs = equation.run(inp);
+    let nr_inp = inp.len().max(1);
+    let mut jac = input_jacobian(equation, inp);
+    for (row, f) in jac.chunks_mut(nr_inp).zip(outputs.iter()) {
+        for (d, x) in row.iter_mut().zip(inp.iter()) {
+            *d *= x / f;
+        }
+    }
+    jac
+}
+
+/// Central finite differences of `f` at `x`, row-major `nr_out x x.len()`.
+pub(crate) fn central_difference<F>(x: &[f64], mut f: F) -> Vec<f64>
+where
+    F: FnMut(&[f64]) -> Vec<f64>,
+{
+    let nr_params = x.len();
+    let mut columns: Vec<Vec<f64>> = Vec::with_capacity(nr_params);
+
+    let mut shifted = x.to_vec();
+    for j in 0..nr_params {
+        let h = 1.0e-6 * x[j].abs().max(1.0);
+        shifted[j] = x[j] + h;
+        let plus = f(&shifted);
+        shifted[j] = x[j] - h;
+        let minus = f(&shifted);
+        shifted[j] = x[j];
+        columns.push(plus.iter().zip(minus.iter()).map(|(p, m)| (p - m) / (2.0 * h)).collect());
+    }
+
+    let nr_out = columns.first().map_or(0, |c| c.len());
+    (0..nr_out).flat_map(|k| columns.iter().map(move |c| c[k])).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    /// Central difference of output `k` of `f` along `x[j]`.
+    fn numeric(f: &dyn Fn(&[f64]) -> Vec<f64>, x: &[f64], j: usize, k: usize) -> f64 {
+        let h = 1.0e-6;
+        let (mut plus, mut minus) = (x.to_vec(), x.to_vec());
+        plus[j] += h;
+        minus[j] -= h;
+        (f(&plus)[k] - f(&minus)[k]) / (2.0 * h)
+    }
+
+    #[test]
+    fn test_analytic_derivatives() {
+        for (id, eq) in EQUATIONS.iter().enumerate() {
+            let (out, cns, inp) = (eq.params)();
+            let constants: Vec<f64> = (0..cns.len()).map(|i| 1.5 + i as f64).collect();
+
+            for t in [0.3, 0.7, 1.1] {
+                let x = vec![t; inp.len()];
+                let equation = (eq.new)(&constants);
+
+                let d_inp = equation.input_derivatives(&x).unwrap_or_else(|| panic!("#{} {}", id, eq.desc));
+                let d_cns = equation.constant_derivatives(&x).unwrap_or_else(|| panic!("#{} {}", id, eq.desc));
+                assert_eq!(d_inp.len(), out.len() * inp.len());
+                assert_eq!(d_cns.len(), out.len() * cns.len());
+
+                for k in 0..out.len() {
+                    for j in 0..inp.len() {
+                        let expected = numeric(&|x| (eq.new)(&constants).run(x), &x, j, k);
+                        assert!((d_inp[k * inp.len() + j] - expected).abs() < 1.0e-6, "#{} {} ∂y{}/∂x{}", id, eq.desc, k, j);
+                    }
+                    for j in 0..cns.len() {
+                        let expected = numeric(&|c| (eq.new)(c).run(&x), &constants, j, k);
+                        assert!((d_cns[k * cns.len() + j] - expected).abs() < 1.0e-6, "#{} {} ∂y{}/∂c{}", id, eq.desc, k, j);
+                    }
+                }
+                assert_eq!(constant_jacobian(id, &constants, &x), d_cns);
+            }
+        }
+    }
+
+    /// Equation without analytic derivatives, `y = x0*exp(x1)`.
+    struct Growth;
+
+    impl Equation for Growth {
+        fn run(&mut self, inp: &[f64]) -> Vec<f64> {
+            vec![inp[0] * inp[1].exp()]
+        }
+
+        fn nr_inputs(&self) -> usize {
+            2
+        }
+    }
+
+    #[test]
+    fn test_finite_difference_fallback() {
+        let x = [2.0, 0.5];
+        let jac = input_jacobian(&mut Growth, &x);
+        assert!((jac[0] - 0.5_f64.exp()).abs() < 1.0e-6);
+        assert!((jac[1] - 2.0 * 0.5_f64.exp()).abs() < 1.0e-6);
+
+        // Relative errors add in quadrature, σ(y)/y = sqrt((σ0/x0)² + σ1²)
+        let sigma = propagate_uncertainty(&mut Growth, &x, &[0.02, 0.01]);
+        let expected = 2.0 * 0.5_f64.exp() * (0.01_f64 * 0.01 + 0.01 * 0.01).sqrt();
+        assert!((sigma[0] - expected).abs() < 1.0e-6);
+
+        let elasticity = sensitivity(&mut Growth, &x);
+        assert!((elasticity[0] - 1.0).abs() < 1.0e-6);
+        assert!((elasticity[1] - 0.5).abs() < 1.0e-6);
+    }
+
+    #[test]
+    fn test_fitted_uncertainty() {
+        // v = v0 + a*t
+        let id = get_equation_by_typeid(mechanics::linear_motion::const_accel::VelocityEquation::params).unwrap();
+        let inputs: Vec<f64> = (0..10).map(|i| i as f64).collect();
+        let outputs: Vec<f64> = inputs.iter().map(|t| 2.0 + 3.0 * t + if (*t as i32) % 2 == 0 { 0.1 } else { -0.1 }).collect();
+
+        let res = fit_equation(id, &inputs, &outputs, &[0.1], &FitOptions::default());
+        let sigma = propagate_covariance(id, &res.constants, &res.covariance, &[4.5]);
+        // At the mean of inputs prediction error is σ/sqrt(N)
+        assert!((sigma[0] - 0.1 / 10.0_f64.sqrt()).abs() < 1.0e-6);
+    }
+}
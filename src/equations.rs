@@ -17,48 +17,130 @@ pub struct BuildTuple<'a> {
     pub params: fn () -> ParamsUnit,
     /// Function to create an instance of equation
     pub new: fn (&[f64]) -> Box<dyn Equation>,
+    /// Function to estimate constants from measured inputs and outputs
+    pub guess: fn (&[f64], &[f64]) -> Option<Vec<f64>>,
+    /// Function to solve equation for unknown in closed form
+    pub inverse: InverseFn,
+    /// Formula in [Expr](crate::Expr) syntax, numbers and combinations of constants
+    /// are folded into fitted constants; `None` if equation is not a single expression
+    pub expr: Option<&'a str>,
+}
+
+impl BuildTuple<'_> {
+    /// Create new equation, fail if number of constants is wrong.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Error, EQUATIONS};
+    /// let mut equation = EQUATIONS[0].try_make(&[]).unwrap();
+    /// assert!(equation.try_run(&[1.0]).is_ok());
+    /// assert!(EQUATIONS[0].try_make(&[1.0]).is_err());
+    /// ```
+    pub fn try_make(&self, cns: &[f64]) -> Result<Box<dyn Equation>> {
+        check_constants((self.params)(), cns)?;
+        Ok((self.new)(cns))
+    }
 }
 
 /// List/array of all equations.
-pub const EQUATIONS: [BuildTuple; 10] = [
+pub const EQUATIONS: [BuildTuple; 14] = [
+    BuildTuple {
+        desc:    "Circumference of circle `C = 2*Pi*r`",
+        params:  figure::circle::CirclePerimeter::params,
+        new:     figure::circle::CirclePerimeter::make,
+        guess:   figure::circle::CirclePerimeter::guess,
+        inverse: figure::circle::CirclePerimeter::inverse,
+        expr:    Some("c0*x0")},
+    BuildTuple {
+        desc:    "Area of circle `A = Pi*r^2`",
+        params:  figure::circle::CircleArea::params,
+        new:     figure::circle::CircleArea::make,
+        guess:   figure::circle::CircleArea::guess,
+        inverse: figure::circle::CircleArea::inverse,
+        expr:    Some("c0*x0*x0")},
+    BuildTuple {
+        desc:    "Perimeter of square `P = 4*side`",
+        params:  figure::rectangle::SquarePerimeter::params,
+        new:     figure::rectangle::SquarePerimeter::make,
+        guess:   figure::rectangle::SquarePerimeter::guess,
+        inverse: figure::rectangle::SquarePerimeter::inverse,
+        expr:    Some("c0*x0")},
+    BuildTuple {
+        desc:    "Area of square `A = side*side`",
+        params:  figure::rectangle::SquareArea::params,
+        new:     figure::rectangle::SquareArea::make,
+        guess:   figure::rectangle::SquareArea::guess,
+        inverse: figure::rectangle::SquareArea::inverse,
+        expr:    Some("x0*x0")},
     BuildTuple {
-        desc:   "Circumference of circle `C = 2*Pi*r`",
-        params: figure::circle::CirclePerimeter::params,
-        new:    figure::circle::CirclePerimeter::make},
+        desc:    "Sine wave `v = A*sin(Speed*t + Phase) + Offset`",
+        params:  function::wave::Sine::params,
+        new:     function::wave::Sine::make,
+        guess:   function::wave::Sine::guess,
+        inverse: function::wave::Sine::inverse,
+        expr:    Some("c0*sin(c1*x0 + c2) + c3")},
     BuildTuple {
-        desc:   "Area of circle `A = Pi*r^2`",
-        params: figure::circle::CircleArea::params,
-        new:    figure::circle::CircleArea::make},
+        desc:    "Sawtooth wave",
+        params:  function::wave::Sawtooth::params,
+        new:     function::wave::Sawtooth::make,
+        guess:   function::wave::Sawtooth::guess,
+        inverse: function::wave::Sawtooth::inverse,
+        expr:    None},
     BuildTuple {
-        desc:   "Perimeter of square `P = 4*side`",
-        params: figure::rectangle::SquarePerimeter::params,
-        new:    figure::rectangle::SquarePerimeter::make},
+        desc:    "Linear motion const accel velocity `v = v0 + a*t`",
+        params:  mechanics::linear_motion::const_accel::VelocityEquation::params,
+        new:     mechanics::linear_motion::const_accel::VelocityEquation::make,
+        guess:   mechanics::linear_motion::const_accel::VelocityEquation::guess,
+        inverse: mechanics::linear_motion::const_accel::VelocityEquation::inverse,
+        expr:    Some("c0 + c1*x0")},
     BuildTuple {
-        desc:   "Area of square `A = side*side`",
-        params: figure::rectangle::SquareArea::params,
-        new:    figure::rectangle::SquareArea::make},
+        desc:    "Linear motion const accel velocity `v = sqrt(v0^2 + 2*a*s)`",
+        params:  mechanics::linear_motion::const_accel::VelocityByDistEquation::params,
+        new:     mechanics::linear_motion::const_accel::VelocityByDistEquation::make,
+        guess:   mechanics::linear_motion::const_accel::VelocityByDistEquation::guess,
+        inverse: mechanics::linear_motion::const_accel::VelocityByDistEquation::inverse,
+        expr:    Some("sqrt(c0 + c1*x0)")},
     BuildTuple {
-        desc:   "Sine wave `v = A*sin(Speed*t + Phase) + Offset`",
-        params: function::wave::Sine::params,
-        new:    function::wave::Sine::make},
+        desc:    "Linear motion const accel distance `s = v0*t + (a*t^2)/2`",
+        params:  mechanics::linear_motion::const_accel::DistanceEquation::params,
+        new:     mechanics::linear_motion::const_accel::DistanceEquation::make,
+        guess:   mechanics::linear_motion::const_accel::DistanceEquation::guess,
+        inverse: mechanics::linear_motion::const_accel::DistanceEquation::inverse,
+        expr:    Some("c0*x0 + c1*x0*x0")},
     BuildTuple {
-        desc:   "Sawtooth wave",
-        params: function::wave::Sawtooth::params,
-        new:    function::wave::Sawtooth::make},
+        desc:    "Linear motion const accel distance `s = t*(v0 + v)/2`",
+        params:  mechanics::linear_motion::const_accel::DistanceByVelEquation::params,
+        new:     mechanics::linear_motion::const_accel::DistanceByVelEquation::make,
+        guess:   mechanics::linear_motion::const_accel::DistanceByVelEquation::guess,
+        inverse: mechanics::linear_motion::const_accel::DistanceByVelEquation::inverse,
+        expr:    Some("c0*x0")},
     BuildTuple {
-        desc:   "Linear motion const accel velocity `v = v0 + a*t`",
-        params: mechanics::linear_motion::const_accel::VelocityEquation::params,
-        new:    mechanics::linear_motion::const_accel::VelocityEquation::make},
+        desc:    "Linear motion const accel distance and velocity `s = v0*t + (a*t^2)/2, v = v0 + a*t`",
+        params:  mechanics::linear_motion::const_accel::DistanceVelocityEquation::params,
+        new:     mechanics::linear_motion::const_accel::DistanceVelocityEquation::make,
+        guess:   mechanics::linear_motion::const_accel::DistanceVelocityEquation::guess,
+        inverse: mechanics::linear_motion::const_accel::DistanceVelocityEquation::inverse,
+        expr:    None},
     BuildTuple {
-        desc:  "Linear motion const accel velocity `v = sqrt(v0^2 + 2*a*s)`",
-        params: mechanics::linear_motion::const_accel::VelocityByDistEquation::params,
-        new:    mechanics::linear_motion::const_accel::VelocityByDistEquation::make},
+        desc:    "Linear motion const velocity distance `s = v*t`",
+        params:  mechanics::linear_motion::const_velocity::DistanceEquation::params,
+        new:     mechanics::linear_motion::const_velocity::DistanceEquation::make,
+        guess:   mechanics::linear_motion::const_velocity::DistanceEquation::guess,
+        inverse: mechanics::linear_motion::const_velocity::DistanceEquation::inverse,
+        expr:    Some("c0*x0")},
     BuildTuple {
-        desc:  "Linear motion const accel distance `s = v0*t + (a*t^2)/2`",
-        params: mechanics::linear_motion::const_accel::DistanceEquation::params,
-        new:    mechanics::linear_motion::const_accel::DistanceEquation::make},
+        desc:    "Newton's second law `F = m*a`",
+        params:  mechanics::dynamics::ForceEquation::params,
+        new:     mechanics::dynamics::ForceEquation::make,
+        guess:   mechanics::dynamics::ForceEquation::guess,
+        inverse: mechanics::dynamics::ForceEquation::inverse,
+        expr:    Some("c0*x0")},
     BuildTuple {
-        desc:  "Linear motion const accel distance `s = t*(v0 + v)/2`",
-        params: mechanics::linear_motion::const_accel::DistanceByVelEquation::params,
-        new:    mechanics::linear_motion::const_accel::DistanceByVelEquation::make},
+        desc:    "Kinetic energy `E = (m*v^2)/2`",
+        params:  mechanics::dynamics::KineticEnergyEquation::params,
+        new:     mechanics::dynamics::KineticEnergyEquation::make,
+        guess:   mechanics::dynamics::KineticEnergyEquation::guess,
+        inverse: mechanics::dynamics::KineticEnergyEquation::inverse,
+        expr:    Some("c0*x0*x0")},
 ];
\ No newline at end of file
@@ -0,0 +1,118 @@
+//! Errors of equations and regression.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+use std::fmt;
+use rustamath_mks::MksUnit;
+
+/// Error of fallible equation and regression functions.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Wrong number of input parameters of equation
+    InputLength {
+        /// Number of input parameters of equation
+        expected: usize,
+        /// Number of provided inputs
+        actual: usize,
+    },
+    /// Wrong number of constant parameters of equation
+    ConstantLength {
+        /// Number of constant parameters of equation
+        expected: usize,
+        /// Number of provided constants
+        actual: usize,
+    },
+    /// Measured inputs and outputs do not split into same number of measurements
+    DataLength {
+        /// Number of input parameters per measurement
+        nr_inputs: usize,
+        /// Number of output parameters per measurement
+        nr_outputs: usize,
+        /// Length of measured inputs
+        inputs_len: usize,
+        /// Length of measured outputs
+        outputs_len: usize,
+    },
+    /// Sigmas are neither empty, per output parameter nor per output value
+    SigmaLength {
+        /// Number of measured output values
+        expected: usize,
+        /// Number of provided sigmas
+        actual: usize,
+    },
+    /// No equation with such index in `EQUATIONS`
+    NoEquation(usize),
+    /// Malformed expression text, position of the first unexpected character
+    Syntax(usize),
+    /// Output unit is not a product of powers of input units, so no dimensionless group has output
+    NotDimensionless,
+    /// Wrong number of known output values of equation to solve
+    OutputLength {
+        /// Number of output parameters of equation
+        expected: usize,
+        /// Number of provided output values
+        actual: usize,
+    },
+    /// Unknown to solve for is not a parameter of equation
+    InvalidUnknown,
+    /// Constant or input other than unknown is missing, or no output is known
+    Underdetermined,
+    /// Input value has unit other than input parameter of equation
+    UnitMismatch {
+        /// Index of input
+        index: usize,
+        /// Unit of input parameter of equation
+        expected: MksUnit,
+        /// Unit of provided value
+        actual: MksUnit,
+    },
+    /// Equation does not declare units of its parameters
+    NoUnits,
+    /// Wrong number of output units of regression, expression search finds laws of one output
+    OutputUnitCount {
+        /// Number of output units the search takes
+        expected: usize,
+        /// Number of provided output units
+        actual: usize,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InputLength { expected, actual } =>
+                write!(f, "equation takes {} inputs, got {}", expected, actual),
+            Error::ConstantLength { expected, actual } =>
+                write!(f, "equation takes {} constants, got {}", expected, actual),
+            Error::DataLength { nr_inputs, nr_outputs, inputs_len, outputs_len } =>
+                write!(f, "{} input values with {} inputs per measurement do not match {} output values with {} outputs per measurement",
+                    inputs_len, nr_inputs, outputs_len, nr_outputs),
+            Error::SigmaLength { expected, actual } =>
+                write!(f, "expected 0, per output or {} sigmas, got {}", expected, actual),
+            Error::NoEquation(id) =>
+                write!(f, "no equation #{}", id),
+            Error::Syntax(position) =>
+                write!(f, "syntax error in expression at position {}", position),
+            Error::NotDimensionless =>
+                write!(f, "output unit is not a product of powers of input units"),
+            Error::OutputLength { expected, actual } =>
+                write!(f, "equation has {} outputs, got {} output values", expected, actual),
+            Error::InvalidUnknown =>
+                write!(f, "unknown is not a parameter of equation"),
+            Error::Underdetermined =>
+                write!(f, "not enough known values to solve for unknown"),
+            Error::UnitMismatch { index, expected, actual } =>
+                write!(f, "input {} has unit {}, expected {}", index, actual, expected),
+            Error::NoUnits =>
+                write!(f, "equation does not declare units of its parameters"),
+            Error::OutputUnitCount { expected, actual } =>
+                write!(f, "search takes {} output units, got {}", expected, actual),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Result of fallible equation and regression functions.
+pub type Result<T> = std::result::Result<T, Error>;
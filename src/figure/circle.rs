@@ -4,7 +4,7 @@
 //! MIT license
 //!
 use rustamath_mks::*;
-use super::super::{EqParams, Equation, EquationMaker, ParamsUnit};
+use super::super::{EqParams, Equation, EquationMaker, Formula, ParamsUnit, Real, Unknown};
 
 /// Perimeter of circle
 pub struct CirclePerimeter {
@@ -45,7 +45,12 @@ impl CirclePerimeter {
     /// ```
     pub fn calc(&mut self, r: f64) {
         self.radius.val = r;
-        self.perimeter.val = 2.0 * std::f64::consts::PI * r;
+        self.perimeter.val = Self::perimeter(r);
+    }
+
+    /// Perimeter of circle with radius `r`.
+    pub fn perimeter<T: Real>(r: T) -> T {
+        T::from(2.0 * std::f64::consts::PI) * r
     }
 }
 
@@ -57,6 +62,13 @@ impl EquationMaker for CirclePerimeter {
     fn make(_cns: &[f64]) -> Box<dyn Equation> {
         Box::new(CirclePerimeter::new())
     }
+
+    fn inverse(unknown: Unknown, out: &[f64], _cns: &[f64], _inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => Some(vec![out[0] / (2.0 * std::f64::consts::PI)]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for CirclePerimeter {
@@ -64,6 +76,33 @@ impl Equation for CirclePerimeter {
         self.calc(inp[0]);
         vec![self.perimeter.val]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.perimeter.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for CirclePerimeter {
+    fn constants(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    fn formula<T: Real>(_cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::perimeter(inp[0])]
+    }
 }
 
 /// Area of circle
@@ -105,7 +144,12 @@ impl CircleArea {
     /// ```
     pub fn calc(&mut self, r: f64) {
         self.radius.val = r;
-        self.area.val = std::f64::consts::PI * r * r;
+        self.area.val = Self::area(r);
+    }
+
+    /// Area of circle with radius `r`.
+    pub fn area<T: Real>(r: T) -> T {
+        T::from(std::f64::consts::PI) * r * r
     }
 }
 
@@ -117,6 +161,13 @@ impl EquationMaker for CircleArea {
     fn make(_cns: &[f64]) -> Box<dyn Equation> {
         Box::new(CircleArea::new())
     }
+
+    fn inverse(unknown: Unknown, out: &[f64], _cns: &[f64], _inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => Some(vec![(out[0] / std::f64::consts::PI).sqrt()]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for CircleArea {
@@ -124,4 +175,31 @@ impl Equation for CircleArea {
         self.calc(inp[0]);
         vec![self.area.val]
     }
-}
\ No newline at end of file
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.area.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for CircleArea {
+    fn constants(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    fn formula<T: Real>(_cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::area(inp[0])]
+    }
+}
@@ -4,7 +4,7 @@
 //! MIT license
 //!
 use rustamath_mks::*;
-use super::super::{EqParams, Equation, EquationMaker, ParamsUnit};
+use super::super::{EqParams, Equation, EquationMaker, Formula, ParamsUnit, Real, Unknown};
 
 /// Perimeter of square
 pub struct SquarePerimeter {
@@ -45,7 +45,12 @@ impl SquarePerimeter {
     /// ```
     pub fn calc(&mut self, side: f64) {
         self.side.val = side;
-        self.perimeter.val = 4.0 * side;
+        self.perimeter.val = Self::perimeter(side);
+    }
+
+    /// Perimeter of square with given side.
+    pub fn perimeter<T: Real>(side: T) -> T {
+        T::from(4.0) * side
     }
 }
 
@@ -57,6 +62,13 @@ impl EquationMaker for SquarePerimeter {
     fn make(_cns: &[f64]) -> Box<dyn Equation> {
         Box::new(SquarePerimeter::new())
     }
+
+    fn inverse(unknown: Unknown, out: &[f64], _cns: &[f64], _inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => Some(vec![out[0] / 4.0]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for SquarePerimeter {
@@ -64,6 +76,33 @@ impl Equation for SquarePerimeter {
         self.calc(inp[0]);
         vec![self.perimeter.val]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.perimeter.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for SquarePerimeter {
+    fn constants(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    fn formula<T: Real>(_cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::perimeter(inp[0])]
+    }
 }
 
 /// Area of square
@@ -105,7 +144,12 @@ impl SquareArea {
     /// ```
     pub fn calc(&mut self, s: f64) {
         self.side.val = s;
-        self.area.val = s * s;
+        self.area.val = Self::area(s);
+    }
+
+    /// Area of square with side `s`.
+    pub fn area<T: Real>(s: T) -> T {
+        s * s
     }
 }
 
@@ -117,6 +161,13 @@ impl EquationMaker for SquareArea {
     fn make(_cns: &[f64]) -> Box<dyn Equation> {
         Box::new(SquareArea::new())
     }
+
+    fn inverse(unknown: Unknown, out: &[f64], _cns: &[f64], _inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => Some(vec![out[0].sqrt()]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for SquareArea {
@@ -124,4 +175,31 @@ impl Equation for SquareArea {
         self.calc(inp[0]);
         vec![self.area.val]
     }
-}
\ No newline at end of file
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.area.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for SquareArea {
+    fn constants(&self) -> Vec<f64> {
+        vec![]
+    }
+
+    fn formula<T: Real>(_cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::area(inp[0])]
+    }
+}
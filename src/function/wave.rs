@@ -4,7 +4,7 @@
 //! MIT license
 //!
 use rustamath_mks::*;
-use super::super::{EqParams, Equation, EquationMaker, ParamsUnit};
+use super::super::{EqParams, Equation, EquationMaker, Formula, ParamsUnit, Real};
 
 /// Sine
 pub struct Sine {
@@ -39,7 +39,12 @@ impl Sine {
     /// Calculate sine.
     pub fn calc(&mut self, angle: f64) {
         self.angle = angle;
-        self.output = (angle*self.speed + self.phase).sin()*self.amplitude + self.shift;
+        self.output = Self::sine(self.amplitude, self.speed, self.phase, self.shift, angle);
+    }
+
+    /// Sine `A*sin(speed*angle + phase) + shift`.
+    pub fn sine<T: Real>(amplitude: T, speed: T, phase: T, shift: T, angle: T) -> T {
+        (angle*speed + phase).sin()*amplitude + shift
     }
 }
 
@@ -48,6 +53,33 @@ impl Equation for Sine {
         self.calc(inp[0]);
         vec![self.output]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.output;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for Sine {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.amplitude, self.speed, self.phase, self.shift]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::sine(cns[0], cns[1], cns[2], cns[3], inp[0])]
+    }
 }
 
 impl EquationMaker for Sine {
@@ -58,6 +90,25 @@ impl EquationMaker for Sine {
     fn make(cns: &[f64]) -> Box<dyn Equation> {
         Box::new(Sine::new(cns[0], cns[1], cns[2], cns[3]))
     }
+
+    /// Amplitude and shift from output range and mean, speed and phase from
+    /// the dominant frequency of the data.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{EquationMaker, function::wave::Sine};
+    /// let inputs: Vec<f64> = (0..50).map(|i| 0.1 * i as f64).collect();
+    /// let outputs: Vec<f64> = inputs.iter().map(|t| 2.0 * (3.0 * t + 0.4).sin() + 1.0).collect();
+    /// let cns = Sine::guess(&inputs, &outputs).unwrap();
+    /// assert!((cns[0] - 2.0).abs() < 0.1);
+    /// assert!((cns[1] - 3.0).abs() < 0.3);
+    /// ```
+    fn guess(inputs: &[f64], outputs: &[f64]) -> Option<Vec<f64>> {
+        let (min, max, mean) = output_range(outputs)?;
+        let (speed, phase) = dominant_frequency(inputs, outputs, mean)?;
+        Some(vec![(max - min) / 2.0, speed, phase, mean])
+    }
 }
 
 /// Sawtooth
@@ -92,10 +143,15 @@ impl Sawtooth {
 
     /// Calculate sawtooth.
     pub fn calc(&mut self, teta: f64) {
+        self.output = Self::sawtooth(self.amplitude, self.speed, self.phase, self.shift, teta);
+    }
+
+    /// Sawtooth of `speed*teta + phase` scaled by amplitude and shifted.
+    pub fn sawtooth<T: Real>(amplitude: T, speed: T, phase: T, shift: T, teta: T) -> T {
         use std::f64::consts::PI;
-        let angle = (teta*self.speed + self.phase).rem_euclid(2.0 * PI); // negative teta?
-        let sawtooth = if angle < PI { angle } else { angle - 2.0 * PI };
-        self.output = sawtooth*self.amplitude + self.shift;
+        let angle = (teta*speed + phase).rem_euclid(2.0 * PI);
+        let sawtooth = if angle.value() < PI { angle } else { angle - T::from(2.0 * PI) };
+        sawtooth*amplitude + shift
     }
 }
 
@@ -104,6 +160,33 @@ impl Equation for Sawtooth {
         self.calc(inp[0]);
         vec![self.output]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.output;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for Sawtooth {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.amplitude, self.speed, self.phase, self.shift]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::sawtooth(cns[0], cns[1], cns[2], cns[3], inp[0])]
+    }
 }
 
 impl EquationMaker for Sawtooth {
@@ -114,4 +197,68 @@ impl EquationMaker for Sawtooth {
     fn make(cns: &[f64]) -> Box<dyn Equation> {
         Box::new(Sawtooth::new(cns[0], cns[1], cns[2], cns[3]))
     }
-}
\ No newline at end of file
+
+    /// Amplitude and shift from output range, speed and phase from
+    /// the dominant frequency of the data.
+    fn guess(inputs: &[f64], outputs: &[f64]) -> Option<Vec<f64>> {
+        use std::f64::consts::PI;
+        let (min, max, mean) = output_range(outputs)?;
+        let (speed, phase) = dominant_frequency(inputs, outputs, mean)?;
+        Some(vec![(max - min) / (2.0 * PI), speed, phase, (max + min) / 2.0])
+    }
+}
+
+/// Minimum, maximum and mean of outputs.
+fn output_range(outputs: &[f64]) -> Option<(f64, f64, f64)> {
+    if outputs.is_empty() {
+        return None;
+    }
+    let min = outputs.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = outputs.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mean = outputs.iter().sum::<f64>() / outputs.len() as f64;
+    if min.is_finite() && max.is_finite() { Some((min, max, mean)) } else { None }
+}
+
+/// Angular speed and phase of the strongest harmonic `sin(speed*t + phase)` of outputs.
+///
+/// Discrete Fourier transform is evaluated on a grid of frequencies from one
+/// period over the whole input span up to the Nyquist limit of the smallest
+/// input step, inputs need not be evenly spaced.
+fn dominant_frequency(inputs: &[f64], outputs: &[f64], mean: f64) -> Option<(f64, f64)> {
+    use std::f64::consts::PI;
+    const MAX_GRID_SIZE: usize = 4096;
+
+    if inputs.len() != outputs.len() || inputs.len() < 3 {
+        return None;
+    }
+
+    let mut sorted = inputs.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let span = sorted[sorted.len() - 1] - sorted[0];
+    let min_step = sorted.windows(2).map(|w| w[1] - w[0]).filter(|step| *step > 0.0).fold(f64::INFINITY, f64::min);
+    if !(span > 0.0 && min_step.is_finite()) {
+        return None;
+    }
+
+    let min_speed = 2.0 * PI / span;
+    let max_speed = PI / min_step;
+    let grid_step = (min_speed / 4.0).max((max_speed - min_speed) / MAX_GRID_SIZE as f64);
+
+    // (power, speed, phase) of the best harmonic
+    let mut best = (0.0_f64, min_speed, 0.0_f64);
+    let mut speed = min_speed;
+    while speed <= max_speed {
+        let (mut re, mut im) = (0.0_f64, 0.0_f64);
+        for (t, v) in inputs.iter().zip(outputs.iter()) {
+            re += (v - mean) * (speed * t).sin();
+            im += (v - mean) * (speed * t).cos();
+        }
+        let power = re * re + im * im;
+        if power > best.0 {
+            best = (power, speed, im.atan2(re));
+        }
+        speed += grid_step;
+    }
+
+    Some((best.1, best.2))
+}
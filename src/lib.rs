@@ -8,17 +8,43 @@
 //! - <https://en.wikipedia.org/wiki/Lists_of_physics_equations>
 //! - [Deep symbolic regression for physics guided by units constraints](https://arxiv.org/pdf/2303.03192.pdf)
 //!
-use rustamath_mks::MksUnit;
+use rustamath_mks::{MksUnit, MksVal};
 
 pub mod figure;
 pub mod function;
 pub mod mechanics;
 
+mod error;
+pub use self::error::{Error, Result};
+
 mod equations;
 pub use self::equations::{EQUATIONS};
 
+mod number;
+pub use self::number::{Dual, Real};
+
+mod derivative;
+pub use self::derivative::{input_jacobian, constant_jacobian, propagate_uncertainty, propagate_covariance, sensitivity};
+
+mod solve;
+pub use self::solve::{solve_equation, Knowns, SolveOptions, Unknown};
+
 mod regression;
-pub use self::regression::find_equation;
+pub use self::regression::{find_equation, find_equation_with, find_equation_weighted, find_expression, find_expression_with};
+pub use self::regression::{goodness_of_fit, goodness_of_fit_with, try_goodness_of_fit};
+pub use self::regression::{try_find_equation, try_find_expression_with, find_expression_seeded};
+pub use self::regression::{fit_equation, find_equation_fits, find_equation_ranked, FitResult};
+pub use self::regression::{Criterion, Scores};
+pub use self::regression::{find_equation_seeded, Budget, RegressionConfig};
+pub use self::regression::{find_pareto_front, Candidate, ParetoPoint};
+pub use self::regression::{validate_equation, find_equation_validated, Validation, ValidationMode, ValidationScore};
+pub use self::regression::{validate_equation_seeded, find_equation_validated_seeded};
+pub use self::regression::{FitMethod, FitOptions, Loss, PoolOptions, Progress};
+pub use self::regression::{Expr, ExprFit, ExprSearch};
+pub use self::regression::{find_expression_gp, try_find_expression_gp, GpOptions};
+pub use self::regression::{find_expression_exhaustive, try_find_expression_exhaustive, find_expression_exhaustive_seeded, ExhaustiveSearch};
+pub use self::regression::{find_expression_dimensionless, pi_groups, DimensionlessProblem, PiGroup};
+pub use self::regression::expr;
 
 /// Equation parameters
 pub struct EqParams<const NR_OUT: usize, const NR_CONST: usize, const NR_IN: usize> {
@@ -33,6 +59,9 @@ pub struct EqParams<const NR_OUT: usize, const NR_CONST: usize, const NR_IN: usi
 /// Parameters unit
 pub type ParamsUnit = (&'static [MksUnit], &'static [MksUnit], &'static [MksUnit]);
 
+/// Closed-form inverse, see [EquationMaker::inverse]
+pub type InverseFn = fn (Unknown, &[f64], &[f64], &[f64]) -> Option<Vec<f64>>;
+
 /// Equation creation interface
 pub trait EquationMaker {
     /// Return tuple with paramerts type
@@ -40,12 +69,262 @@ pub trait EquationMaker {
 
     /// Create new equation with provided constant parameters.
     fn make(cns: &[f64]) -> Box<dyn Equation>;
+
+    /// Create new equation, fail if number of constants is wrong.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{EquationMaker, Error};
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// assert!(ForceEquation::try_make(&[2.0]).is_ok());
+    /// assert_eq!(ForceEquation::try_make(&[]).err(), Some(Error::ConstantLength { expected: 1, actual: 0 }));
+    /// ```
+    fn try_make(cns: &[f64]) -> Result<Box<dyn Equation>> {
+        check_constants(Self::params(), cns)?;
+        Ok(Self::make(cns))
+    }
+
+    /// Estimate constant parameters from measured inputs and outputs,
+    /// used as starting point of fitting; `None` if equation has no estimate.
+    fn guess(_inputs: &[f64], _outputs: &[f64]) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Closed-form roots for a constant or input unknown from outputs, constants and inputs,
+    /// value of the unknown is NaN; `None` if equation has no inverse for it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{EquationMaker, Unknown};
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// // Acceleration `a = F/m`
+    /// assert_eq!(ForceEquation::inverse(Unknown::Input(0), &[6.0], &[2.0], &[f64::NAN]), Some(vec![3.0]));
+    /// ```
+    fn inverse(_unknown: Unknown, _out: &[f64], _cns: &[f64], _inp: &[f64]) -> Option<Vec<f64>> {
+        None
+    }
 }
 
 /// Equation interface
 pub trait Equation {
     /// Run equation with provided input parameters.
     fn run(&mut self, inp: &[f64]) -> Vec<f64>;
+
+    /// Number of input parameters; by default from [Equation::units],
+    /// 0 if equation declares neither.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// assert_eq!(ForceEquation::make(&[2.0]).nr_inputs(), 1);
+    /// ```
+    fn nr_inputs(&self) -> usize {
+        self.units().map_or(0, |(_out, _cns, inp)| inp.len())
+    }
+
+    /// Run equation and write outputs to `out` instead of allocating them.
+    ///
+    /// Default copies result of [Equation::run], equations override it
+    /// to avoid allocation.
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        out.copy_from_slice(&self.run(inp));
+    }
+
+    /// Run equation for many measurements at once.
+    ///
+    /// `inputs` holds `nr_inputs()` values per measurement, outputs of every
+    /// measurement are written to `outputs` one after another, so its length
+    /// is number of measurements times number of outputs.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+    /// let mut eq = DistanceVelocityEquation::make(&[2.0, 3.0]);
+    /// let mut outputs = [0.0; 4];
+    /// eq.run_batch(&[1.0, 10.0], &mut outputs);
+    /// assert_eq!(outputs, [2.0 + 1.5, 5.0, 20.0 + 150.0, 32.0]);
+    /// ```
+    fn run_batch(&mut self, inputs: &[f64], outputs: &mut [f64]) {
+        let nr_inputs = self.nr_inputs().max(1);
+        let nr_measurements = inputs.len() / nr_inputs;
+        if nr_measurements == 0 {
+            return;
+        }
+        let nr_outputs = outputs.len() / nr_measurements;
+        for (inp, out) in inputs.chunks(nr_inputs).zip(outputs.chunks_mut(nr_outputs)) {
+            self.run_into(inp, out);
+        }
+    }
+
+    /// Analytic derivatives of outputs with respect to inputs,
+    /// row-major `nr_out x nr_inp`; `None` if equation does not provide them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+    /// let eq = VelocityEquation::make(&[2.0, 3.0]);
+    /// // ∂v/∂t = a
+    /// assert_eq!(eq.input_derivatives(&[10.0]), Some(vec![3.0]));
+    /// ```
+    fn input_derivatives(&self, _inp: &[f64]) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Analytic derivatives of outputs with respect to constants,
+    /// row-major `nr_out x nr_cns`; `None` if equation does not provide them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+    /// let eq = VelocityEquation::make(&[2.0, 3.0]);
+    /// // ∂v/∂v0 = 1, ∂v/∂a = t
+    /// assert_eq!(eq.constant_derivatives(&[10.0]), Some(vec![1.0, 10.0]));
+    /// ```
+    fn constant_derivatives(&self, _inp: &[f64]) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Run equation, fail if number of inputs is wrong.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Equation, EquationMaker, Error};
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// let mut eq = ForceEquation::make(&[2.0]);
+    /// assert_eq!(eq.try_run(&[9.8]), Ok(vec![2.0 * 9.8]));
+    /// assert_eq!(eq.try_run(&[]), Err(Error::InputLength { expected: 1, actual: 0 }));
+    /// ```
+    fn try_run(&mut self, inp: &[f64]) -> Result<Vec<f64>> {
+        if inp.len() != self.nr_inputs() {
+            return Err(Error::InputLength { expected: self.nr_inputs(), actual: inp.len() });
+        }
+        Ok(self.run(inp))
+    }
+
+    /// Units of output, constant and input parameters; `None` if equation does not declare them.
+    fn units(&self) -> Option<ParamsUnit> {
+        None
+    }
+
+    /// Run equation on values with units, fail if number or unit of inputs is wrong.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{Equation, EquationMaker, Error};
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+    /// use rustamath_mks::*;
+    /// let mut eq = VelocityEquation::make(&[2.0, 3.0]);
+    /// let v = eq.run_checked(&[MksVal {val: 10.0, unit: TIME_UNIT}]).unwrap();
+    /// assert_eq!(v[0].val, 32.0);
+    /// assert!(v[0].unit == VELOCITY_UNIT);
+    /// assert_eq!(eq.run_checked(&[MksVal {val: 10.0, unit: DISTANCE_UNIT}]).err(),
+    ///     Some(Error::UnitMismatch { index: 0, expected: TIME_UNIT, actual: DISTANCE_UNIT }));
+    /// ```
+    fn run_checked(&mut self, inp: &[MksVal]) -> Result<Vec<MksVal>> {
+        let (out_units, _cns_units, inp_units) = self.units().ok_or(Error::NoUnits)?;
+        if inp.len() != inp_units.len() {
+            return Err(Error::InputLength { expected: inp_units.len(), actual: inp.len() });
+        }
+        if let Some((index, (value, unit))) = inp.iter().zip(inp_units.iter()).enumerate()
+            .find(|(_, (value, unit))| value.unit != **unit)
+        {
+            return Err(Error::UnitMismatch { index, expected: *unit, actual: value.unit });
+        }
+
+        let values: Vec<f64> = inp.iter().map(|x| x.val).collect();
+        Ok(self.run(&values).into_iter().zip(out_units.iter())
+            .map(|(val, unit)| MksVal { val, unit: *unit })
+            .collect())
+    }
+}
+
+/// Equation which formula is generic over [Real] numbers.
+///
+/// Derivatives are computed exactly by evaluating the formula on [Dual] numbers,
+/// one pass per variable.
+pub trait Formula {
+    /// Values of constant parameters.
+    fn constants(&self) -> Vec<f64>;
+
+    /// Outputs of the equation for given constants and inputs.
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T>;
+
+    /// Derivatives of outputs with respect to inputs, row-major `nr_out x nr_inp`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::Formula;
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceEquation;
+    /// let eq = DistanceEquation::new(2.0, 3.0);
+    /// // ∂s/∂t = v0 + a*t
+    /// assert_eq!(eq.dual_input_derivatives(&[10.0]), vec![32.0]);
+    /// ```
+    fn dual_input_derivatives(&self, inp: &[f64]) -> Vec<f64> {
+        let cns: Vec<Dual> = self.constants().into_iter().map(Dual::from).collect();
+        dual_jacobian(inp, |x| Self::formula(&cns, x))
+    }
+
+    /// Derivatives of outputs with respect to constants, row-major `nr_out x nr_cns`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::Formula;
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceEquation;
+    /// let eq = DistanceEquation::new(2.0, 3.0);
+    /// // ∂s/∂v0 = t, ∂s/∂a = t^2/2
+    /// assert_eq!(eq.dual_constant_derivatives(&[10.0]), vec![10.0, 50.0]);
+    /// ```
+    fn dual_constant_derivatives(&self, inp: &[f64]) -> Vec<f64> {
+        let inp: Vec<Dual> = inp.iter().copied().map(Dual::from).collect();
+        dual_jacobian(&self.constants(), |c| Self::formula(c, &inp))
+    }
+}
+
+/// Jacobian of `f` at `x` by forward-mode differentiation, row-major `nr_out x x.len()`.
+fn dual_jacobian<F>(x: &[f64], f: F) -> Vec<f64>
+where
+    F: Fn(&[Dual]) -> Vec<Dual>,
+{
+    let nr_params = x.len();
+    let mut variables: Vec<Dual> = x.iter().copied().map(Dual::from).collect();
+    let mut jac: Vec<f64> = Vec::new();
+
+    for j in 0..nr_params {
+        variables[j].der = 1.0;
+        let outputs = f(&variables);
+        variables[j].der = 0.0;
+        if jac.is_empty() {
+            jac = vec![0.0; outputs.len() * nr_params];
+        }
+        for (k, y) in outputs.iter().enumerate() {
+            jac[k * nr_params + j] = y.der;
+        }
+    }
+
+    jac
+}
+
+/// Check number of constants against equation parameters.
+fn check_constants(params: ParamsUnit, cns: &[f64]) -> Result<()> {
+    let (_out, cns_params, _inp) = params;
+    if cns.len() != cns_params.len() {
+        return Err(Error::ConstantLength { expected: cns_params.len(), actual: cns.len() });
+    }
+    Ok(())
 }
 
 /// Get list of equations that have specified input/output unit types.
@@ -85,7 +364,7 @@ pub fn find_equation_by_units(inputs: &[MksUnit], outputs: &[MksUnit]) -> Vec<us
 ///    assert_eq!(equation.run(&[10.0])[0], 2.0 * std::f64::consts::PI * 10.0);
 /// }
 /// ```
-#[allow(clippy::fn_address_comparisons)]
+#[allow(unpredictable_function_pointer_comparisons)]
 pub fn get_equation_by_typeid(typeid: fn () -> ParamsUnit) -> Option<usize> {
     for (index, eq) in EQUATIONS.iter().enumerate() {
         if typeid == eq.params {
@@ -93,4 +372,63 @@ pub fn get_equation_by_typeid(typeid: fn () -> ParamsUnit) -> Option<usize> {
         }
     }
     None
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::regression::Model;
+    use super::regression::expr::ExprModel;
+    use rustamath_mks::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_generic_formula() {
+        use mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+        use function::wave::Sawtooth;
+
+        let mut eq = DistanceVelocityEquation::new(2.0, 3.0);
+        assert_eq!(DistanceVelocityEquation::formula(&eq.constants(), &[10.0]), eq.run(&[10.0]));
+
+        // Same formula on dual numbers gives value and derivative with respect to time
+        let out = DistanceVelocityEquation::formula(&[Dual::from(2.0), Dual::from(3.0)], &[Dual::variable(10.0)]);
+        assert_eq!(out, vec![Dual { val: 170.0, der: 32.0 }, Dual { val: 32.0, der: 3.0 }]);
+
+        // Derivative of sawtooth is slope between jumps
+        let saw = Sawtooth::new(2.0, 0.5, 0.0, 1.0);
+        assert_eq!(saw.dual_input_derivatives(&[1.0]), vec![1.0]);
+        assert_eq!(saw.dual_input_derivatives(&[8.0]), vec![1.0]);
+    }
+
+    #[test]
+    fn test_run_checked() {
+        for (id, eq) in EQUATIONS.iter().enumerate() {
+            let (out, cns, inp) = (eq.params)();
+            let constants: Vec<f64> = (0..cns.len()).map(|i| 1.5 + i as f64).collect();
+            let values: Vec<f64> = (0..inp.len()).map(|i| 0.5 + i as f64).collect();
+            let mut equation = (eq.new)(&constants);
+
+            let inputs: Vec<MksVal> = values.iter().zip(inp.iter()).map(|(val, unit)| MksVal { val: *val, unit: *unit }).collect();
+            let outputs = equation.run_checked(&inputs).unwrap_or_else(|e| panic!("#{} {}: {}", id, eq.desc, e));
+            assert_eq!(outputs.iter().map(|y| y.val).collect::<Vec<f64>>(), equation.run(&values));
+            assert!(outputs.iter().zip(out.iter()).all(|(y, unit)| y.unit == *unit), "#{} {}", id, eq.desc);
+        }
+    }
+
+    #[test]
+    fn test_unit_mismatch() {
+        use mechanics::linear_motion::const_accel::DistanceEquation;
+        let mut eq = DistanceEquation::make(&[2.0, 3.0]);
+
+        let err = eq.run_checked(&[MksVal { val: 10.0, unit: DISTANCE_UNIT }]).unwrap_err();
+        assert_eq!(err, Error::UnitMismatch { index: 0, expected: TIME_UNIT, actual: DISTANCE_UNIT });
+        assert_eq!(err.to_string(), format!("input 0 has unit {}, expected {}", DISTANCE_UNIT, TIME_UNIT));
+
+        assert_eq!(eq.run_checked(&[]).err(), Some(Error::InputLength { expected: 1, actual: 0 }));
+
+        // Expressions do not declare units
+        let expr: Expr = "c0*x0".parse().unwrap();
+        let mut eq = ExprModel { expr: Rc::new(expr), nr_inp: 1 }.make(&[2.0]);
+        assert_eq!(eq.run_checked(&[MksVal { val: 1.0, unit: TIME_UNIT }]).err(), Some(Error::NoUnits));
+    }
+}
@@ -8,4 +8,5 @@
 //! - <https://en.wikipedia.org/wiki/List_of_equations_in_classical_mechanics>
 //!
 
-pub mod linear_motion;
\ No newline at end of file
+pub mod linear_motion;
+pub mod dynamics;
\ No newline at end of file
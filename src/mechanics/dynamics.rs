@@ -0,0 +1,252 @@
+//! Dynamics, Newton's laws of motion and energy.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/List_of_equations_in_classical_mechanics>
+//!
+use rustamath_mks::*;
+use super::super::{EqParams, Equation, EquationMaker, ParamsUnit, Unknown};
+
+/// Force formula parameters type
+pub const FORCE_EQ_PARAMS: EqParams<1, 1, 1> = EqParams {
+    out: [NEWTON_UNIT], cns: [KILOGRAM_UNIT], inp: [ACCEL_UNIT]};
+
+/// Newton's second law equation
+pub struct ForceEquation {
+    /// Force `F = m*a`.
+    pub force: MksVal,
+    /// Mass
+    pub mass: MksVal,
+    /// Acceleration
+    pub acceleration: MksVal,
+}
+
+impl ForceEquation {
+    /// Parameters type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// use rustamath_mks::*;
+    /// assert!(ForceEquation::PARAMS.out == [NEWTON_UNIT]);
+    /// assert!(ForceEquation::PARAMS.cns == [KILOGRAM_UNIT]);
+    /// ```
+    pub const PARAMS: EqParams<1, 1, 1> = FORCE_EQ_PARAMS;
+
+    /// Initialize constants
+    pub fn new(m: f64) -> ForceEquation {
+        ForceEquation {
+            force: MksVal {val: 0.0, unit: NEWTON_UNIT},
+            mass: MksVal {val: m, unit: KILOGRAM_UNIT},
+            acceleration: MksVal {val: 0.0, unit: ACCEL_UNIT},
+        }
+    }
+
+    /// Calculate force by acceleration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// let mut eq = ForceEquation::new(2.0);
+    /// eq.calc(9.8);
+    /// assert_eq!(eq.force.val, 2.0 * 9.8);
+    /// ```
+    pub fn calc(&mut self, a: f64) {
+        self.acceleration.val = a;
+        self.force = self.mass * self.acceleration;
+    }
+}
+
+impl EquationMaker for ForceEquation {
+    /// Get parameters type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// use rustamath_physics::EquationMaker;
+    /// use rustamath_mks::*;
+    /// let params = ForceEquation::params();
+    /// assert!(params.0 == &[NEWTON_UNIT]);
+    /// assert!(params.2 == &[ACCEL_UNIT]);
+    /// ```
+    fn params() -> ParamsUnit {
+        (&Self::PARAMS.out, &Self::PARAMS.cns, &Self::PARAMS.inp)
+    }
+
+    /// Create new equation with constant parameters provided.
+    fn make(cns: &[f64]) -> Box<dyn Equation> {
+        Box::new(ForceEquation::new(cns[0]))
+    }
+
+    /// Acceleration or mass in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => Some(vec![out[0] / cns[0]]),
+            Unknown::Constant(0) => Some(vec![out[0] / inp[0]]),
+            _ => None,
+        }
+    }
+}
+
+impl Equation for ForceEquation {
+    /// Run equation with inputs provided.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::ForceEquation;
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// let mut eq = ForceEquation::make(&[2.0]);
+    /// let res = eq.run(&[9.8]);
+    /// assert_eq!(res[0], 2.0 * 9.8);
+    /// ```
+    fn run(&mut self, inp: &[f64]) -> Vec<f64> {
+        self.calc(inp[0]);
+        vec![self.force.val]
+    }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.force.val;
+    }
+
+    fn input_derivatives(&self, _inp: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![self.mass.val])
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![inp[0]])
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+/// Kinetic energy formula parameters type
+pub const KINETIC_ENERGY_EQ_PARAMS: EqParams<1, 1, 1> = EqParams {
+    out: [JOULE_UNIT], cns: [KILOGRAM_UNIT], inp: [VELOCITY_UNIT]};
+
+/// Kinetic energy equation
+pub struct KineticEnergyEquation {
+    /// Kinetic energy `E = (m*v^2)/2`.
+    pub energy: MksVal,
+    /// Mass
+    pub mass: MksVal,
+    /// Velocity
+    pub velocity: MksVal,
+}
+
+impl KineticEnergyEquation {
+    /// Parameters type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::KineticEnergyEquation;
+    /// use rustamath_mks::*;
+    /// assert!(KineticEnergyEquation::PARAMS.out == [JOULE_UNIT]);
+    /// assert!(KineticEnergyEquation::PARAMS.inp == [VELOCITY_UNIT]);
+    /// ```
+    pub const PARAMS: EqParams<1, 1, 1> = KINETIC_ENERGY_EQ_PARAMS;
+
+    /// Initialize constants
+    pub fn new(m: f64) -> KineticEnergyEquation {
+        KineticEnergyEquation {
+            energy: MksVal {val: 0.0, unit: JOULE_UNIT},
+            mass: MksVal {val: m, unit: KILOGRAM_UNIT},
+            velocity: MksVal {val: 0.0, unit: VELOCITY_UNIT},
+        }
+    }
+
+    /// Calculate kinetic energy by velocity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::KineticEnergyEquation;
+    /// let mut eq = KineticEnergyEquation::new(2.0);
+    /// eq.calc(3.0);
+    /// assert_eq!(eq.energy.val, 2.0 * 3.0 * 3.0 / 2.0);
+    /// ```
+    pub fn calc(&mut self, v: f64) {
+        self.velocity.val = v;
+        self.energy = (self.mass * self.velocity * self.velocity) / MksVal::new_scalar(2.0);
+    }
+}
+
+impl EquationMaker for KineticEnergyEquation {
+    /// Get parameters type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::KineticEnergyEquation;
+    /// use rustamath_physics::EquationMaker;
+    /// use rustamath_mks::*;
+    /// let params = KineticEnergyEquation::params();
+    /// assert!(params.0 == &[JOULE_UNIT]);
+    /// assert!(params.1 == &[KILOGRAM_UNIT]);
+    /// ```
+    fn params() -> ParamsUnit {
+        (&Self::PARAMS.out, &Self::PARAMS.cns, &Self::PARAMS.inp)
+    }
+
+    /// Create new equation with constant parameters provided.
+    fn make(cns: &[f64]) -> Box<dyn Equation> {
+        Box::new(KineticEnergyEquation::new(cns[0]))
+    }
+
+    /// Velocity, both signs, or mass in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => {
+                let v = (2.0 * out[0] / cns[0]).sqrt();
+                Some(vec![-v, v])
+            },
+            Unknown::Constant(0) => Some(vec![2.0 * out[0] / (inp[0] * inp[0])]),
+            _ => None,
+        }
+    }
+}
+
+impl Equation for KineticEnergyEquation {
+    /// Run equation with inputs provided.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::dynamics::KineticEnergyEquation;
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// let mut eq = KineticEnergyEquation::make(&[2.0]);
+    /// let res = eq.run(&[3.0]);
+    /// assert_eq!(res[0], 9.0);
+    /// ```
+    fn run(&mut self, inp: &[f64]) -> Vec<f64> {
+        self.calc(inp[0]);
+        vec![self.energy.val]
+    }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.energy.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![self.mass.val * inp[0]])
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![inp[0] * inp[0] / 2.0])
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
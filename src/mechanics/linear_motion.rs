@@ -9,6 +9,7 @@
 //!
 
 pub mod const_accel;
+pub mod const_velocity;
 
 #[cfg(test)]
 mod tests;
\ No newline at end of file
@@ -8,7 +8,7 @@
 //! - <https://en.wikipedia.org/wiki/List_of_equations_in_classical_mechanics>
 //!
 use rustamath_mks::*;
-use super::super::super::{EqParams, Equation, EquationMaker, ParamsUnit};
+use super::super::super::{EqParams, Equation, EquationMaker, Formula, ParamsUnit, Real, Unknown};
 
 /// Velocity formula parameters type
 pub const VELOCITY_EQ_PARAMS: EqParams<1, 2, 1> = EqParams {
@@ -64,6 +64,11 @@ impl VelocityEquation {
         self.velocity = self.initial_velocity +
             self.acceleration * self.time;
     }
+
+    /// Velocity `v = v0 + a*t`.
+    pub fn velocity<T: Real>(v0: T, a: T, t: T) -> T {
+        v0 + a * t
+    }
 }
 
 impl EquationMaker for VelocityEquation {
@@ -87,6 +92,17 @@ impl EquationMaker for VelocityEquation {
     fn make(cns: &[f64]) -> Box<dyn Equation> {
         Box::new(VelocityEquation::new(cns[0], cns[1]))
     }
+
+    /// Time, initial velocity or acceleration in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        let (v, v0, a, t) = (out[0], cns[0], cns[1], inp[0]);
+        match unknown {
+            Unknown::Input(0) => Some(vec![(v - v0) / a]),
+            Unknown::Constant(0) => Some(vec![v - a * t]),
+            Unknown::Constant(1) => Some(vec![(v - v0) / t]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for VelocityEquation {
@@ -105,6 +121,33 @@ impl Equation for VelocityEquation {
         self.calc(inp[0]);
         vec![self.velocity.val]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.velocity.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for VelocityEquation {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.initial_velocity.val, self.acceleration.val]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::velocity(cns[0], cns[1], inp[0])]
+    }
 }
 
 /// Velocity-by-distance formula parameters type
@@ -161,6 +204,11 @@ impl VelocityByDistEquation {
         self.velocity = ((self.initial_velocity * self.initial_velocity) +
             (MksVal::new_scalar(2.0) * self.acceleration * self.distance)).sqrt();
     }
+
+    /// Velocity `v = sqrt(v0^2 + 2*a*s)`.
+    pub fn velocity<T: Real>(v0: T, a: T, s: T) -> T {
+        (v0 * v0 + T::from(2.0) * a * s).sqrt()
+    }
 }
 
 impl EquationMaker for VelocityByDistEquation {
@@ -184,6 +232,20 @@ impl EquationMaker for VelocityByDistEquation {
     fn make(cns: &[f64]) -> Box<dyn Equation> {
         Box::new(VelocityByDistEquation::new(cns[0], cns[1]))
     }
+
+    /// Distance, initial velocity of both signs or acceleration in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        let (v, v0, a, s) = (out[0], cns[0], cns[1], inp[0]);
+        match unknown {
+            Unknown::Input(0) => Some(vec![(v * v - v0 * v0) / (2.0 * a)]),
+            Unknown::Constant(0) => {
+                let v0 = (v * v - 2.0 * a * s).sqrt();
+                Some(vec![-v0, v0])
+            },
+            Unknown::Constant(1) => Some(vec![(v * v - v0 * v0) / (2.0 * s)]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for VelocityByDistEquation {
@@ -202,6 +264,33 @@ impl Equation for VelocityByDistEquation {
         self.calc(inp[0]);
         vec![self.velocity.val]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.velocity.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for VelocityByDistEquation {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.initial_velocity.val, self.acceleration.val]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::velocity(cns[0], cns[1], inp[0])]
+    }
 }
 
 /// Distance formula parameters type
@@ -259,6 +348,11 @@ impl DistanceEquation {
             self.initial_velocity * self.time +
             (self.acceleration * self.time * self.time) / MksVal::new_scalar(2.0);
     }
+
+    /// Distance `s = v0*t + a*t^2/2`.
+    pub fn distance<T: Real>(v0: T, a: T, t: T) -> T {
+        v0 * t + (a * t * t) / T::from(2.0)
+    }
 }
 
 impl EquationMaker for DistanceEquation {
@@ -282,6 +376,17 @@ impl EquationMaker for DistanceEquation {
     fn make(cns: &[f64]) -> Box<dyn Equation> {
         Box::new(DistanceEquation::new(cns[0], cns[1]))
     }
+
+    /// Both times, initial velocity or acceleration in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        let (s, v0, a, t) = (out[0], cns[0], cns[1], inp[0]);
+        match unknown {
+            Unknown::Input(0) => Some(quadratic_roots(a / 2.0, v0, -s)),
+            Unknown::Constant(0) => Some(vec![(s - a * t * t / 2.0) / t]),
+            Unknown::Constant(1) => Some(vec![2.0 * (s - v0 * t) / (t * t)]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for DistanceEquation {
@@ -300,6 +405,33 @@ impl Equation for DistanceEquation {
         self.calc(inp[0]);
         vec![self.distance.val]
     }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.distance.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for DistanceEquation {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.initial_velocity.val, self.acceleration.val]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::distance(cns[0], cns[1], inp[0])]
+    }
 }
 
 /// Distance-by-velocity formula parameters type
@@ -357,6 +489,11 @@ impl DistanceByVelEquation {
             (self.initial_velocity + self.final_velocity) *
             self.time * MksVal::new_scalar(0.5);
     }
+
+    /// Distance `s = (v0 + v)*t/2`.
+    pub fn distance<T: Real>(v0: T, v: T, t: T) -> T {
+        (v0 + v) * t * T::from(0.5)
+    }
 }
 
 impl EquationMaker for DistanceByVelEquation {
@@ -380,6 +517,17 @@ impl EquationMaker for DistanceByVelEquation {
     fn make(cns: &[f64]) -> Box<dyn Equation> {
         Box::new(DistanceByVelEquation::new(cns[0], cns[1]))
     }
+
+    /// Time, initial or final velocity in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        let (s, v0, v, t) = (out[0], cns[0], cns[1], inp[0]);
+        match unknown {
+            Unknown::Input(0) => Some(vec![2.0 * s / (v0 + v)]),
+            Unknown::Constant(0) => Some(vec![2.0 * s / t - v]),
+            Unknown::Constant(1) => Some(vec![2.0 * s / t - v0]),
+            _ => None,
+        }
+    }
 }
 
 impl Equation for DistanceByVelEquation {
@@ -398,4 +546,187 @@ impl Equation for DistanceByVelEquation {
         self.calc(inp[0]);
         vec![self.distance.val]
     }
-}
\ No newline at end of file
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.distance.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for DistanceByVelEquation {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.initial_velocity.val, self.final_velocity.val]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![Self::distance(cns[0], cns[1], inp[0])]
+    }
+}
+
+/// Distance-and-velocity formula parameters type
+pub const DISTANCE_VELOCITY_EQ_PARAMS: EqParams<2, 2, 1> = EqParams {
+    out: [DISTANCE_UNIT, VELOCITY_UNIT], cns: [VELOCITY_UNIT, ACCEL_UNIT], inp: [TIME_UNIT]};
+
+/// Distance-and-velocity equation, two outputs
+pub struct DistanceVelocityEquation {
+    /// Distance `s = v0*t + (a*t^2)/2`.
+    pub distance: MksVal,
+    /// Velocity `v = v0 + at`.
+    pub velocity: MksVal,
+    /// Initial velocity
+    pub initial_velocity: MksVal,
+    /// Constant acceleration
+    pub acceleration: MksVal,
+    /// Time
+    pub time: MksVal,
+}
+
+impl DistanceVelocityEquation {
+    /// Parameters type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+    /// use rustamath_mks::*;
+    /// assert!(DistanceVelocityEquation::PARAMS.out == [DISTANCE_UNIT, VELOCITY_UNIT]);
+    /// assert!(DistanceVelocityEquation::PARAMS.cns == [VELOCITY_UNIT, ACCEL_UNIT]);
+    /// ```
+    pub const PARAMS: EqParams<2, 2, 1> = DISTANCE_VELOCITY_EQ_PARAMS;
+
+    /// Initialize constants
+    pub fn new(v0: f64, a: f64) -> DistanceVelocityEquation {
+        DistanceVelocityEquation {
+            distance: MksVal {val: 0.0, unit: DISTANCE_UNIT},
+            velocity: MksVal {val: 0.0, unit: VELOCITY_UNIT},
+            initial_velocity: MksVal {val: v0, unit: VELOCITY_UNIT},
+            acceleration: MksVal {val: a, unit: ACCEL_UNIT},
+            time: MksVal {val: 0.0, unit: TIME_UNIT},
+        }
+    }
+
+    /// Calculate distance and velocity by time with constant acceleration.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+    /// let mut eq = DistanceVelocityEquation::new(2.0, 3.0);
+    /// eq.calc(10.0);
+    /// assert_eq!(eq.distance.val, (2.0 * 10.0) + (3.0 * 100.0)/2.0);
+    /// assert_eq!(eq.velocity.val, 32.0);
+    /// ```
+    pub fn calc(&mut self, t: f64) {
+        self.time.val = t;
+        self.distance =
+            self.initial_velocity * self.time +
+            (self.acceleration * self.time * self.time) / MksVal::new_scalar(2.0);
+        self.velocity = self.initial_velocity +
+            self.acceleration * self.time;
+    }
+}
+
+impl EquationMaker for DistanceVelocityEquation {
+    /// Get parameters type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+    /// use rustamath_physics::EquationMaker;
+    /// use rustamath_mks::*;
+    /// let params = DistanceVelocityEquation::params();
+    /// assert!(params.0 == &[DISTANCE_UNIT, VELOCITY_UNIT]);
+    /// assert!(params.2 == &[TIME_UNIT]);
+    /// ```
+    fn params() -> ParamsUnit {
+        (&Self::PARAMS.out, &Self::PARAMS.cns, &Self::PARAMS.inp)
+    }
+
+    /// Create new equation with constant parameters provided.
+    fn make(cns: &[f64]) -> Box<dyn Equation> {
+        Box::new(DistanceVelocityEquation::new(cns[0], cns[1]))
+    }
+
+    /// Solve velocity equation if velocity is known, distance equation otherwise.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        // Velocity is linear in every unknown, use it if known
+        if out[1].is_nan() {
+            DistanceEquation::inverse(unknown, &out[..1], cns, inp)
+        } else {
+            VelocityEquation::inverse(unknown, &out[1..], cns, inp)
+        }
+    }
+}
+
+impl Equation for DistanceVelocityEquation {
+    /// Run equation with inputs provided.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceVelocityEquation;
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// let mut eq = DistanceVelocityEquation::make(&[2.0, 3.0]);
+    /// let res = eq.run(&[10.0]);
+    /// assert_eq!(res, vec![(2.0 * 10.0) + (3.0 * 100.0)/2.0, 32.0]);
+    /// ```
+    fn run(&mut self, inp: &[f64]) -> Vec<f64> {
+        self.calc(inp[0]);
+        vec![self.distance.val, self.velocity.val]
+    }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.distance.val;
+        out[1] = self.velocity.val;
+    }
+
+    fn input_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_input_derivatives(inp))
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(self.dual_constant_derivatives(inp))
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
+
+impl Formula for DistanceVelocityEquation {
+    fn constants(&self) -> Vec<f64> {
+        vec![self.initial_velocity.val, self.acceleration.val]
+    }
+
+    fn formula<T: Real>(cns: &[T], inp: &[T]) -> Vec<T> {
+        vec![DistanceEquation::distance(cns[0], cns[1], inp[0]), VelocityEquation::velocity(cns[0], cns[1], inp[0])]
+    }
+}
+
+/// Real roots of `a*x^2 + b*x + c = 0`, single root if equation is linear.
+fn quadratic_roots(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a == 0.0 {
+        return vec![-c / b];
+    }
+    let d = b * b - 4.0 * a * c;
+    if d < 0.0 {
+        return vec![];
+    }
+    // Avoid cancellation of `-b + sqrt(d)`
+    let q = -0.5 * (b + b.signum() * d.sqrt());
+    if q == 0.0 { vec![0.0] } else { vec![q / a, c / q] }
+}
@@ -0,0 +1,130 @@
+//! Linear motion with constant velocity.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/List_of_equations_in_classical_mechanics>
+//!
+use rustamath_mks::*;
+use super::super::super::{EqParams, Equation, EquationMaker, ParamsUnit, Unknown};
+
+/// Distance formula parameters type
+pub const DISTANCE_EQ_PARAMS: EqParams<1, 1, 1> = EqParams {
+    out: [DISTANCE_UNIT], cns: [VELOCITY_UNIT], inp: [TIME_UNIT]};
+
+/// Distance equation
+pub struct DistanceEquation {
+    /// Distance `s = v*t`.
+    pub distance: MksVal,
+    /// Constant velocity
+    pub velocity: MksVal,
+    /// Time
+    pub time: MksVal,
+}
+
+impl DistanceEquation {
+    /// Parameters type
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_velocity::DistanceEquation;
+    /// use rustamath_mks::*;
+    /// assert!(DistanceEquation::PARAMS.out == [DISTANCE_UNIT]);
+    /// assert!(DistanceEquation::PARAMS.cns == [VELOCITY_UNIT]);
+    /// ```
+    pub const PARAMS: EqParams<1, 1, 1> = DISTANCE_EQ_PARAMS;
+
+    /// Initialize constants
+    pub fn new(v: f64) -> DistanceEquation {
+        DistanceEquation {
+            distance: MksVal {val: 0.0, unit: DISTANCE_UNIT},
+            velocity: MksVal {val: v, unit: VELOCITY_UNIT},
+            time: MksVal {val: 0.0, unit: TIME_UNIT},
+        }
+    }
+
+    /// Calculate distance by time with constant velocity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_velocity::DistanceEquation;
+    /// let mut eq = DistanceEquation::new(3.0);
+    /// eq.calc(10.0);
+    /// assert_eq!(eq.distance.val, 30.0);
+    /// ```
+    pub fn calc(&mut self, t: f64) {
+        self.time.val = t;
+        self.distance = self.velocity * self.time;
+    }
+}
+
+impl EquationMaker for DistanceEquation {
+    /// Get parameters type.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_velocity::DistanceEquation;
+    /// use rustamath_physics::EquationMaker;
+    /// use rustamath_mks::*;
+    /// let params = DistanceEquation::params();
+    /// assert!(params.0 == &[DISTANCE_UNIT]);
+    /// assert!(params.1 == &[VELOCITY_UNIT]);
+    /// ```
+    fn params() -> ParamsUnit {
+        (&Self::PARAMS.out, &Self::PARAMS.cns, &Self::PARAMS.inp)
+    }
+
+    /// Create new equation with constant parameters provided.
+    fn make(cns: &[f64]) -> Box<dyn Equation> {
+        Box::new(DistanceEquation::new(cns[0]))
+    }
+
+    /// Time or velocity in closed form.
+    fn inverse(unknown: Unknown, out: &[f64], cns: &[f64], inp: &[f64]) -> Option<Vec<f64>> {
+        match unknown {
+            Unknown::Input(0) => Some(vec![out[0] / cns[0]]),
+            Unknown::Constant(0) => Some(vec![out[0] / inp[0]]),
+            _ => None,
+        }
+    }
+}
+
+impl Equation for DistanceEquation {
+    /// Run equation with inputs provided.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::mechanics::linear_motion::const_velocity::DistanceEquation;
+    /// use rustamath_physics::{Equation, EquationMaker};
+    /// let mut eq = DistanceEquation::make(&[3.0]);
+    /// let res = eq.run(&[10.0]);
+    /// assert_eq!(res[0], 30.0);
+    /// ```
+    fn run(&mut self, inp: &[f64]) -> Vec<f64> {
+        self.calc(inp[0]);
+        vec![self.distance.val]
+    }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        self.calc(inp[0]);
+        out[0] = self.distance.val;
+    }
+
+    fn input_derivatives(&self, _inp: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![self.velocity.val])
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some(vec![inp[0]])
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some(Self::params())
+    }
+}
@@ -18,4 +18,33 @@ fn find_velocity_equation() {
 
     // `v = v0 + a*t`
     assert_eq!(res[0], 3.0 + 2.0*10.0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn find_distance_velocity_equation() {
+    let ids = find_equation_by_units(&[TIME_UNIT], &[DISTANCE_UNIT, VELOCITY_UNIT]);
+    assert_eq!(ids.len(), 1);
+
+    let mut equation = (EQUATIONS[ids[0]].new)(&[/*v0*/3.0, /*a*/2.0]);
+
+    let res = equation.run(&[10.0]);
+    assert_eq!(res.len(), 2);
+
+    // `s = v0*t + (a*t^2)/2, v = v0 + a*t`
+    assert_eq!(res[0], 3.0*10.0 + 2.0*100.0/2.0);
+    assert_eq!(res[1], 3.0 + 2.0*10.0);
+}
+
+#[test]
+fn calc_keeps_units() {
+    use super::const_accel::*;
+
+    let mut eq = VelocityByDistEquation::new(3.0, 4.0);
+    eq.calc(2.0);
+    assert_eq!(eq.velocity.unit, VELOCITY_UNIT);
+
+    let mut eq = DistanceVelocityEquation::new(2.0, 3.0);
+    eq.calc(10.0);
+    assert_eq!((eq.distance.unit, eq.velocity.unit), (DISTANCE_UNIT, VELOCITY_UNIT));
+    assert_eq!((eq.distance.val, eq.velocity.val), (170.0, 32.0));
+}
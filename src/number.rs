@@ -0,0 +1,193 @@
+//! Numbers equations are evaluated on.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Dual_number>
+//! - <https://en.wikipedia.org/wiki/Automatic_differentiation#Forward_accumulation>
+//!
+use std::ops::{Add, Sub, Mul, Div, Neg};
+
+/// Real number interface of equation formulas.
+///
+/// Formula written once for any `T: Real` runs on `f64` and on [Dual]
+/// numbers to get exact derivatives.
+pub trait Real:
+    Copy + From<f64> +
+    Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + Div<Output = Self> + Neg<Output = Self>
+{
+    /// Plain value of the number, used to choose a branch of the formula.
+    fn value(self) -> f64;
+
+    /// Sine
+    fn sin(self) -> Self;
+
+    /// Cosine
+    fn cos(self) -> Self;
+
+    /// Square root
+    fn sqrt(self) -> Self;
+
+    /// Exponent `e^self`
+    fn exp(self) -> Self;
+
+    /// Natural logarithm
+    fn ln(self) -> Self;
+
+    /// Raise to real power `n`.
+    fn powf(self, n: f64) -> Self;
+
+    /// Least nonnegative remainder of division by `rhs`.
+    fn rem_euclid(self, rhs: f64) -> Self;
+}
+
+impl Real for f64 {
+    fn value(self) -> f64 { self }
+    fn sin(self) -> Self { f64::sin(self) }
+    fn cos(self) -> Self { f64::cos(self) }
+    fn sqrt(self) -> Self { f64::sqrt(self) }
+    fn exp(self) -> Self { f64::exp(self) }
+    fn ln(self) -> Self { f64::ln(self) }
+    fn powf(self, n: f64) -> Self { f64::powf(self, n) }
+    fn rem_euclid(self, rhs: f64) -> Self { f64::rem_euclid(self, rhs) }
+}
+
+/// Dual number `val + der*ε` with `ε² = 0`.
+///
+/// Function of dual number `x + 1*ε` is `f(x) + f'(x)*ε`,
+/// so evaluating a formula on it gives value and derivative at once.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{Dual, Real};
+/// let x = Dual::variable(3.0);
+/// let y = x * x + Dual::from(2.0) * x;
+/// assert_eq!((y.val, y.der), (15.0, 8.0));
+/// assert_eq!(x.sin().der, 3.0_f64.cos());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Dual {
+    /// Value
+    pub val: f64,
+    /// Derivative
+    pub der: f64,
+}
+
+impl Dual {
+    /// Variable derivatives are taken with respect to, `der = 1`.
+    pub fn variable(val: f64) -> Dual {
+        Dual { val, der: 1.0 }
+    }
+}
+
+impl From<f64> for Dual {
+    /// Constant, `der = 0`.
+    fn from(val: f64) -> Dual {
+        Dual { val, der: 0.0 }
+    }
+}
+
+impl Add for Dual {
+    type Output = Dual;
+    fn add(self, rhs: Dual) -> Dual {
+        Dual { val: self.val + rhs.val, der: self.der + rhs.der }
+    }
+}
+
+impl Sub for Dual {
+    type Output = Dual;
+    fn sub(self, rhs: Dual) -> Dual {
+        Dual { val: self.val - rhs.val, der: self.der - rhs.der }
+    }
+}
+
+impl Mul for Dual {
+    type Output = Dual;
+    fn mul(self, rhs: Dual) -> Dual {
+        Dual { val: self.val * rhs.val, der: self.der * rhs.val + self.val * rhs.der }
+    }
+}
+
+impl Div for Dual {
+    type Output = Dual;
+    fn div(self, rhs: Dual) -> Dual {
+        Dual {
+            val: self.val / rhs.val,
+            der: (self.der * rhs.val - self.val * rhs.der) / (rhs.val * rhs.val),
+        }
+    }
+}
+
+impl Neg for Dual {
+    type Output = Dual;
+    fn neg(self) -> Dual {
+        Dual { val: -self.val, der: -self.der }
+    }
+}
+
+impl Real for Dual {
+    fn value(self) -> f64 {
+        self.val
+    }
+
+    fn sin(self) -> Self {
+        Dual { val: self.val.sin(), der: self.der * self.val.cos() }
+    }
+
+    fn cos(self) -> Self {
+        Dual { val: self.val.cos(), der: -self.der * self.val.sin() }
+    }
+
+    fn sqrt(self) -> Self {
+        let val = self.val.sqrt();
+        Dual { val, der: self.der / (2.0 * val) }
+    }
+
+    fn exp(self) -> Self {
+        let val = self.val.exp();
+        Dual { val, der: self.der * val }
+    }
+
+    fn ln(self) -> Self {
+        Dual { val: self.val.ln(), der: self.der / self.val }
+    }
+
+    fn powf(self, n: f64) -> Self {
+        Dual { val: self.val.powf(n), der: self.der * n * self.val.powf(n - 1.0) }
+    }
+
+    /// Remainder shifts value by a multiple of `rhs`, derivative is kept.
+    fn rem_euclid(self, rhs: f64) -> Self {
+        Dual { val: self.val.rem_euclid(rhs), der: self.der }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exp_ln_powf() {
+        let x = Dual::variable(2.0);
+        assert_eq!(x.exp(), Dual { val: 2.0_f64.exp(), der: 2.0_f64.exp() });
+        assert_eq!(x.ln(), Dual { val: 2.0_f64.ln(), der: 0.5 });
+        assert_eq!(x.powf(3.0), Dual { val: 8.0, der: 12.0 });
+        // d/dx ln(x^2 e^x) = 2/x + 1
+        let y = (x.powf(2.0) * x.exp()).ln();
+        assert!((y.der - 2.0).abs() < 1.0e-12);
+        assert_eq!(Real::powf(2.0_f64, 0.5), 2.0_f64.sqrt());
+    }
+
+    #[test]
+    fn test_dual_numbers() {
+        let x = Dual::variable(2.0);
+        let y = (x * x + Dual::from(5.0)).sqrt() / x;
+        // d/dx sqrt(x² + 5)/x = -5/(x² sqrt(x² + 5))
+        assert!((y.val - 1.5).abs() < 1.0e-12);
+        assert!((y.der + 5.0 / (4.0 * 3.0)).abs() < 1.0e-12);
+        assert_eq!((-x.cos()).der, 2.0_f64.sin());
+    }
+}
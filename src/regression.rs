@@ -6,13 +6,122 @@
 //! Find equation(s) based on input/output unit type and
 //! input/output values.
 use rustamath_mks::*;
-use super::{find_equation_by_units, EQUATIONS};
+use super::{find_equation_by_units, Equation, Error, ParamsUnit, Result, EQUATIONS};
+use super::equations::{BuildTuple};
 
+mod buckingham;
+mod budget;
+mod config;
+mod criteria;
 mod fit;
+mod gp;
+mod linalg;
+mod loss;
+mod pareto;
+mod pool;
+mod rng;
+mod stats;
+mod units;
+mod validate;
+pub mod expr;
+pub use self::expr::{Expr};
+pub use self::buckingham::{pi_groups, DimensionlessProblem, PiGroup};
+pub use self::budget::Budget;
+pub use self::config::RegressionConfig;
+pub use self::criteria::{Criterion, Scores};
+pub use self::fit::{FitMethod, FitOptions};
+pub use self::gp::GpOptions;
+pub use self::loss::Loss;
+pub use self::pareto::{Candidate, ParetoPoint};
+pub use self::pool::{PoolOptions, Progress};
+pub use self::validate::{Validation, ValidationMode, ValidationScore};
 #[cfg(test)]
 mod test_sine;
 #[cfg(test)]
 mod test_circle;
+#[cfg(test)]
+mod test_expr;
+#[cfg(test)]
+mod test_multi_output;
+#[cfg(test)]
+mod test_one_constant;
+#[cfg(test)]
+mod test_levenberg_marquardt;
+#[cfg(test)]
+mod test_fit_result;
+#[cfg(test)]
+mod test_weighted;
+#[cfg(test)]
+mod test_multi_start;
+#[cfg(test)]
+mod test_criteria;
+#[cfg(test)]
+mod test_pareto;
+#[cfg(test)]
+mod test_validate;
+#[cfg(test)]
+mod test_robust;
+#[cfg(test)]
+mod test_errors;
+#[cfg(test)]
+mod test_pool;
+#[cfg(test)]
+mod test_seed;
+#[cfg(test)]
+mod test_budget;
+#[cfg(test)]
+mod test_gp;
+#[cfg(test)]
+mod test_exhaustive;
+#[cfg(test)]
+mod test_buckingham;
+#[cfg(test)]
+mod test_batch;
+
+/// Model which constants are fitted to measured values.
+pub(crate) trait Model {
+    /// Number of output, constant and input parameters.
+    fn dims(&self) -> (usize, usize, usize);
+
+    /// Create equation with provided constant parameters.
+    fn make(&self, cns: &[f64]) -> Box<dyn Equation>;
+
+    /// Units of output, constant and input parameters; `None` if not known.
+    fn units(&self) -> Option<ParamsUnit> {
+        None
+    }
+
+    /// Estimate of constants from measured data; `None` if not available.
+    fn guess(&self, _inputs: &[f64], _outputs: &[f64]) -> Option<Vec<f64>> {
+        None
+    }
+
+    /// Number of nodes in expression tree; by default the smallest binary tree
+    /// with every constant and input as a leaf.
+    fn complexity(&self) -> usize {
+        let (_, nr_cns, nr_inp) = self.dims();
+        (2 * (nr_cns + nr_inp)).max(2) - 1
+    }
+}
+
+impl Model for BuildTuple<'_> {
+    fn dims(&self) -> (usize, usize, usize) {
+        let (out_params, cns_params, inp_params) = (self.params)();
+        (out_params.len(), cns_params.len(), inp_params.len())
+    }
+
+    fn make(&self, cns: &[f64]) -> Box<dyn Equation> {
+        (self.new)(cns)
+    }
+
+    fn units(&self) -> Option<ParamsUnit> {
+        Some((self.params)())
+    }
+
+    fn guess(&self, inputs: &[f64], outputs: &[f64]) -> Option<Vec<f64>> {
+        (self.guess)(inputs, outputs)
+    }
+}
 
 /// Get list of equations that sutisfy specified input/output unit types
 /// and fit to measured input/output values.
@@ -35,31 +144,645 @@ pub fn find_equation(
     outputs: &[f64]
 ) -> Vec<(usize, f64)>
 {
-    use std::thread;
+    find_equation_with(unit_inputs, unit_outputs, inputs, outputs, &FitOptions::default())
+}
+
+/// Get list of equations that fit to measured values, see [find_equation].
+///
+/// Constants are fitted with provided options.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{find_equation_with, FitMethod, FitOptions};
+/// use rustamath_mks::*;
+/// let inputs = [0.0, 1.0, 2.0, 3.0];
+/// let outputs: Vec<f64> = inputs.iter().map(|t| 2.0 * t + 1.5 * t * t).collect();
+/// let options = FitOptions { method: FitMethod::LevenbergMarquardt, ..FitOptions::default() };
+/// let eqs = find_equation_with(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &options);
+/// assert!(eqs[0].1 < 1.0e-9);
+/// ```
+pub fn find_equation_with(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    options: &FitOptions
+) -> Vec<(usize, f64)>
+{
+    expect_data(rank_equations(unit_inputs, unit_outputs, inputs, outputs, &[], options))
+}
+
+/// Get list of equations that fit to measured values with known uncertainties.
+///
+/// Same as [find_equation], but every measured output value is weighted by
+/// its standard deviation, both when constants are fitted and when equations
+/// are scored, see [goodness_of_fit] about `ssigmas`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{find_equation_weighted, EQUATIONS};
+/// use rustamath_mks::*;
+/// let inputs = [0.0, 1.0, 2.0, 3.0, 4.0];
+/// let mut outputs: Vec<f64> = inputs.iter().map(|t| 2.0 * t + 1.5 * t * t).collect();
+/// outputs[4] += 3.0; // unreliable last measurement
+/// let ssigmas = [0.01, 0.01, 0.01, 0.01, 10.0];
+/// let eqs = find_equation_weighted(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &ssigmas);
+/// assert_eq!(EQUATIONS[eqs[0].0].desc, "Linear motion const accel distance `s = v0*t + (a*t^2)/2`");
+/// assert!(eqs[0].1 < 1.0);
+/// ```
+pub fn find_equation_weighted(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64]
+) -> Vec<(usize, f64)>
+{
+    expect_data(rank_equations(unit_inputs, unit_outputs, inputs, outputs, ssigmas, &FitOptions::default()))
+}
+
+/// Get list of equations that fit to measured values, fail on malformed data.
+///
+/// Same as [find_equation], but lengths of measured inputs and outputs are
+/// checked against numbers of input and output units instead of panicking.
+/// Equations with undefined (NaN) fit are sorted last.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{try_find_equation, Error};
+/// use rustamath_mks::*;
+/// let inputs = [0.0, 1.0, 2.0];
+/// assert!(try_find_equation(&[TIME_UNIT], &[VELOCITY_UNIT], &inputs, &[3.0, 5.0, 7.0]).is_ok());
+/// assert!(matches!(try_find_equation(&[TIME_UNIT], &[VELOCITY_UNIT], &inputs, &[3.0, 5.0]),
+///     Err(Error::DataLength { .. })));
+/// ```
+pub fn try_find_equation(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64]
+) -> Result<Vec<(usize, f64)>>
+{
+    rank_equations(unit_inputs, unit_outputs, inputs, outputs, &[], &FitOptions::default())
+}
+
+/// Score all equations with matching units and sort them by reduced χ².
+fn rank_equations(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions
+) -> Result<Vec<(usize, f64)>>
+{
+    check_data(unit_inputs.len(), unit_outputs.len(), inputs, outputs, ssigmas)?;
 
     let ids: Vec<usize> = find_equation_by_units(unit_inputs, unit_outputs);
 
-    let mut eqs: Vec<(usize, f64)> = Vec::new();
+    let eqs: Vec<Result<(usize, f64)>> = pool::map(&ids, &options.pool, |id| {
+        fit_and_score(&EQUATIONS[*id], inputs, outputs, ssigmas, options).map(|res| (*id, res.reduced_chi2))
+    });
+    let mut eqs: Vec<(usize, f64)> = eqs.into_iter().collect::<Result<_>>()?;
 
-    thread::scope(|thread_scope| {
-        let mut ths = Vec::new();
+    eqs.sort_by(|a, b| cmp_nan_last(a.1, b.1));
 
-        for id in ids.iter() {
-            let th = thread_scope.spawn(move || {
-                (*id, goodness_of_fit(*id, inputs, outputs, &[]))
-            });
-            ths.push(th);
+    Ok(eqs)
+}
+
+/// Options of expression tree search.
+#[derive(Debug, Clone)]
+pub struct ExprSearch {
+    /// Maximum number of nodes in expression tree
+    pub max_size: usize,
+    /// Maximum number of returned best fitting expressions
+    pub max_results: usize,
+    /// Units constants may have; build only dimensionally consistent trees when set,
+    /// or ignore units if `None`
+    pub constant_units: Option<Vec<MksUnit>>,
+    /// Options of fitting constants
+    pub fit: FitOptions,
+    /// Criterion to rank expressions
+    pub criterion: Criterion,
+    /// Limits of the search, smaller expressions are fitted first
+    pub budget: Budget,
+}
+
+impl Default for ExprSearch {
+    fn default() -> Self {
+        ExprSearch {
+            max_size: 5,
+            max_results: 10,
+            constant_units: Some(vec![SCALAR_UNIT]),
+            fit: FitOptions::default(),
+            criterion: Criterion::ReducedChi2,
+            budget: Budget::default(),
         }
+    }
+}
+
+impl ExprSearch {
+    /// Regression configuration seeded from `fit.seed`.
+    fn config(&self) -> RegressionConfig {
+        RegressionConfig { seed: self.fit.seed, fit: self.fit.clone(), criterion: self.criterion, budget: self.budget.clone() }
+    }
+}
 
-        for th in ths {
-            let id_with_fit = th.join().unwrap();
-            eqs.push(id_with_fit);
+/// Expression found by regression.
+#[derive(Debug, Clone)]
+pub struct ExprFit {
+    /// Expression tree
+    pub expr: Expr,
+    /// Fitted constants, `c0, c1, ...` in the expression
+    pub constants: Vec<f64>,
+    /// Reduced χ² goodness-of-fit
+    pub chi2: f64,
+    /// Scores of model selection criteria
+    pub scores: Scores,
+}
+
+/// Get list of best fitting expression trees built from primitives.
+///
+/// Unlike [find_equation] this is not limited to equations listed in `EQUATIONS`,
+/// candidate formulas are built from `+, -, *, /, pow, sin, exp, log, sqrt`,
+/// constants and inputs; constants are fitted the same way as for [find_equation].
+///
+/// By default only dimensionally consistent trees with unit-less constants are tried,
+/// see [ExprSearch] to allow constants with units or to ignore units altogether.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::find_expression;
+/// use rustamath_mks::*;
+/// let inputs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let outputs: Vec<f64> = inputs.iter().map(|x| 2.0 * x * x).collect();
+/// let exprs = find_expression(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs);
+/// assert!(exprs[0].chi2 < 1.0e-3);
+/// ```
+pub fn find_expression(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64]
+) -> Vec<ExprFit>
+{
+    find_expression_with(unit_inputs, unit_outputs, inputs, outputs, &ExprSearch::default())
+}
+
+/// Get list of best fitting expression trees, see [find_expression].
+pub fn find_expression_with(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    search: &ExprSearch
+) -> Vec<ExprFit>
+{
+    expect_data(try_find_expression_with(unit_inputs, unit_outputs, inputs, outputs, search))
+}
+
+/// Get list of best fitting expression trees, fail on malformed data.
+///
+/// Same as [find_expression_with], but lengths of measured inputs and outputs
+/// and number of output units are checked instead of panicking.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{try_find_expression_with, Error, ExprSearch};
+/// use rustamath_mks::*;
+/// let res = try_find_expression_with(&[], &[SCALAR_UNIT], &[], &[1.0], &ExprSearch::default());
+/// assert!(matches!(res, Err(Error::DataLength { .. })));
+/// let res = try_find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT; 2], &[1.0], &[1.0, 2.0], &ExprSearch::default());
+/// assert_eq!(res.err(), Some(Error::OutputUnitCount { expected: 1, actual: 2 }));
+/// ```
+pub fn try_find_expression_with(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    search: &ExprSearch
+) -> Result<Vec<ExprFit>>
+{
+    find_expression_seeded(unit_inputs, unit_outputs, inputs, outputs, &[], search, &search.config())
+}
+
+/// Get list of best fitting expression trees, reproducible from seed.
+///
+/// Same as [try_find_expression_with], but constants of every tree are fitted with
+/// options seeded from `config`, see [RegressionConfig], and trees are ranked by
+/// `config.criterion` within `config.budget`; `search.fit`, `search.criterion`
+/// and `search.budget` are not used.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_mks::*;
+/// let inputs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let outputs: Vec<f64> = inputs.iter().map(|x| 2.0 * x * x).collect();
+/// let config = RegressionConfig { seed: 7, fit: FitOptions { starts: 3, ..FitOptions::default() }, ..RegressionConfig::default() };
+/// let exprs = find_expression_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &ExprSearch::default(), &config).unwrap();
+/// let again = find_expression_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &ExprSearch::default(), &config).unwrap();
+/// assert_eq!(exprs[0].constants, again[0].constants);
+/// ```
+pub fn find_expression_seeded(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    search: &ExprSearch,
+    config: &RegressionConfig
+) -> Result<Vec<ExprFit>>
+{
+    check_expression_data(unit_inputs, unit_outputs, inputs, outputs, ssigmas)?;
+
+    let mut exprs: Vec<ExprFit> = fit_expressions(unit_inputs, unit_outputs, inputs, outputs, ssigmas, search, config)?
+        .into_iter()
+        .map(|(expr, res)| ExprFit { expr, constants: res.constants, chi2: res.reduced_chi2, scores: res.scores })
+        .collect();
+
+    exprs.sort_by(|a, b| cmp_nan_last(a.scores.get(config.criterion), b.scores.get(config.criterion)));
+    exprs.truncate(search.max_results);
+
+    Ok(exprs)
+}
+
+/// Get list of best fitting expression trees evolved by genetic programming.
+///
+/// Unlike [find_expression_with] trees are not enumerated, a population of trees
+/// evolves by tournament selection, subtree crossover and mutation, see [GpOptions].
+/// Initial population starts from formulas of `EQUATIONS` entries with matching units,
+/// so known physics is refined rather than rediscovered.
+///
+/// Constants of every tree are fitted with `config.fit`, trees are ranked by
+/// `config.criterion` and the search stops early within `config.budget`.
+/// Same `config.seed` gives same expressions.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_mks::*;
+/// let inputs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let outputs: Vec<f64> = inputs.iter().map(|v| 1.5 * v * v).collect();
+/// let gp = GpOptions { generations: 2, ..GpOptions::default() };
+/// let exprs = find_expression_gp(&[VELOCITY_UNIT], &[JOULE_UNIT], &inputs, &outputs, &[], &gp, &RegressionConfig::default());
+/// assert!(exprs[0].chi2 < 1.0e-6);
+/// ```
+pub fn find_expression_gp(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    gp: &GpOptions,
+    config: &RegressionConfig
+) -> Vec<ExprFit>
+{
+    expect_data(try_find_expression_gp(unit_inputs, unit_outputs, inputs, outputs, ssigmas, gp, config))
+}
+
+/// Get list of expression trees evolved by genetic programming, fail on malformed data.
+///
+/// Same as [find_expression_gp], but lengths of measured inputs, outputs and sigmas
+/// and number of output units are checked instead of panicking.
+pub fn try_find_expression_gp(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    gp: &GpOptions,
+    config: &RegressionConfig
+) -> Result<Vec<ExprFit>>
+{
+    check_expression_data(unit_inputs, unit_outputs, inputs, outputs, ssigmas)?;
+
+    let mut exprs: Vec<ExprFit> = gp::evolve(unit_inputs, unit_outputs, inputs, outputs, ssigmas, gp, config)?
+        .into_iter()
+        .filter(|(_, res)| res.reduced_chi2.is_finite())
+        .map(|(expr, res)| ExprFit { expr, constants: res.constants, chi2: res.reduced_chi2, scores: res.scores })
+        .collect();
+
+    exprs.sort_by(|a, b| cmp_nan_last(a.scores.get(config.criterion), b.scores.get(config.criterion)));
+    exprs.truncate(gp.max_results);
+
+    Ok(exprs)
+}
+
+/// Get list of best fitting expressions found for dimensionless groups of variables.
+///
+/// Inputs and output are replaced by their Buckingham-Π groups, see [DimensionlessProblem],
+/// expression trees of input groups are fitted to the output group as in [find_expression_with],
+/// and found laws are mapped back to output of physical inputs.
+/// Reported χ² and scores are those of the dimensionless fit.
+///
+/// When inputs have no dimensionless group the law is `output group = c0`.
+/// Fail if lengths of data do not match, there is not exactly one output unit
+/// or output unit can not be made dimensionless with inputs.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_mks::*;
+/// // Pendulum period from length and gravity, `T = 2*Pi*sqrt(L/g)`
+/// let inputs = [0.5, 9.8, 1.0, 9.8, 2.0, 9.8, 1.0, 1.6, 2.0, 1.6];
+/// let outputs: Vec<f64> = inputs.chunks(2).map(|x: &[f64]| 2.0 * std::f64::consts::PI * (x[0] / x[1]).sqrt()).collect();
+/// let exprs = find_expression_dimensionless(&[DISTANCE_UNIT, ACCEL_UNIT], &[TIME_UNIT], &inputs, &outputs, &ExprSearch::default()).unwrap();
+/// assert_eq!(exprs[0].expr.to_string(), "sqrt(c0*x0/x1)");
+/// assert!((exprs[0].expr.eval(&[1.0, 9.8], &exprs[0].constants) - outputs[1]).abs() < 1.0e-9);
+/// ```
+pub fn find_expression_dimensionless(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    search: &ExprSearch
+) -> Result<Vec<ExprFit>>
+{
+    let nr_measurements = check_expression_data(unit_inputs, unit_outputs, inputs, outputs, &[])?;
+
+    let problem = DimensionlessProblem::new(unit_inputs, unit_outputs[0])?;
+    let (pi_inputs, pi_outputs) = problem.transform(inputs, outputs);
+
+    let fits: Vec<ExprFit> = if problem.inputs.is_empty() {
+        // Output group is constant, least squares estimate is the mean.
+        let mean = pi_outputs.iter().sum::<f64>() / nr_measurements as f64;
+        let chi2: f64 = pi_outputs.iter().map(|y| (y - mean) * (y - mean)).sum();
+        let reduced_chi2 = chi2 / nr_measurements.saturating_sub(1).max(1) as f64;
+        vec![ExprFit {
+            expr: Expr::Const(0),
+            constants: vec![mean],
+            chi2: reduced_chi2,
+            scores: Scores::new(chi2, reduced_chi2, nr_measurements, 1, 1, false),
+        }]
+    } else {
+        let scalars = vec![SCALAR_UNIT; problem.inputs.len()];
+        let search = ExprSearch {
+            constant_units: search.constant_units.as_ref().map(|_| vec![SCALAR_UNIT]),
+            ..search.clone()
+        };
+        try_find_expression_with(&scalars, &[SCALAR_UNIT], &pi_inputs, &pi_outputs, &search)?
+    };
+
+    Ok(fits.into_iter()
+        .filter_map(|fit| problem.to_physical(&fit.expr).map(|expr| ExprFit { expr, ..fit }))
+        .collect())
+}
+
+/// Options of exhaustive expression search.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::ExhaustiveSearch;
+/// use rustamath_mks::*;
+/// let search = ExhaustiveSearch { max_depth: 4, constant_units: Some(vec![SCALAR_UNIT, ACCEL_UNIT]), ..ExhaustiveSearch::default() };
+/// ```
+#[derive(Debug, Clone)]
+pub struct ExhaustiveSearch {
+    /// Maximum number of levels in expression tree, a leaf is one level
+    pub max_depth: usize,
+    /// Maximum number of nodes in expression tree
+    pub max_size: usize,
+    /// Maximum number of returned best fitting expressions
+    pub max_results: usize,
+    /// Units constants may have; ignore units if `None`
+    pub constant_units: Option<Vec<MksUnit>>,
+    /// Options of fitting constants
+    pub fit: FitOptions,
+    /// Criterion to rank expressions
+    pub criterion: Criterion,
+    /// Limits of the search, smaller expressions are fitted first
+    pub budget: Budget,
+}
+
+impl Default for ExhaustiveSearch {
+    fn default() -> Self {
+        ExhaustiveSearch {
+            max_depth: 3,
+            max_size: 9,
+            max_results: 10,
+            constant_units: Some(vec![SCALAR_UNIT]),
+            fit: FitOptions::default(),
+            criterion: Criterion::ReducedChi2,
+            budget: Budget::default(),
         }
-    });
+    }
+}
 
-    eqs.sort_unstable_by(|a, b| a.1.partial_cmp(&b.1).unwrap());
+impl ExhaustiveSearch {
+    /// Regression configuration seeded from `fit.seed`.
+    fn config(&self) -> RegressionConfig {
+        RegressionConfig { seed: self.fit.seed, fit: self.fit.clone(), criterion: self.criterion, budget: self.budget.clone() }
+    }
+}
 
-    eqs
+/// Get list of best fitting expressions from all formulas up to given depth.
+///
+/// Every dimensionally consistent tree with up to `search.max_depth` levels
+/// and `search.max_size` nodes is built, see [expr::enumerate_to_depth],
+/// algebraically equivalent trees are fitted only once, see [expr::dedupe],
+/// and trees are fitted in order of increasing size.
+/// Unlike [find_expression_gp] no formula within the limits is missed
+/// unless `search.budget` runs out, so this suits problems with few inputs.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_mks::*;
+/// // `v = sqrt(2*a*s)`
+/// let inputs = [1.0, 2.0, 3.0, 4.0, 5.0];
+/// let outputs: Vec<f64> = inputs.iter().map(|s| (2.0_f64 * 9.8 * s).sqrt()).collect();
+/// let search = ExhaustiveSearch { constant_units: Some(vec![SCALAR_UNIT, ACCEL_UNIT]), ..ExhaustiveSearch::default() };
+/// let exprs = find_expression_exhaustive(&[DISTANCE_UNIT], &[VELOCITY_UNIT], &inputs, &outputs, &[], &search);
+/// assert_eq!(exprs[0].expr.to_string(), "sqrt(c0*x0)");
+/// ```
+pub fn find_expression_exhaustive(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    search: &ExhaustiveSearch
+) -> Vec<ExprFit>
+{
+    expect_data(try_find_expression_exhaustive(unit_inputs, unit_outputs, inputs, outputs, ssigmas, search))
+}
+
+/// Get list of best fitting expressions up to given depth, fail on malformed data.
+///
+/// Same as [find_expression_exhaustive], but lengths of measured inputs, outputs
+/// and sigmas and number of output units are checked instead of panicking.
+pub fn try_find_expression_exhaustive(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    search: &ExhaustiveSearch
+) -> Result<Vec<ExprFit>>
+{
+    find_expression_exhaustive_seeded(unit_inputs, unit_outputs, inputs, outputs, ssigmas, search, &search.config())
+}
+
+/// Get list of best fitting expressions up to given depth, reproducible from seed.
+///
+/// Same as [try_find_expression_exhaustive], but constants of every tree are fitted
+/// with options seeded from `config`, see [RegressionConfig], and trees are ranked by
+/// `config.criterion` within `config.budget`; `search.fit`, `search.criterion`
+/// and `search.budget` are not used.
+pub fn find_expression_exhaustive_seeded(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    search: &ExhaustiveSearch,
+    config: &RegressionConfig
+) -> Result<Vec<ExprFit>>
+{
+    check_expression_data(unit_inputs, unit_outputs, inputs, outputs, ssigmas)?;
+
+    let nr_inp_params = unit_inputs.len();
+    let scalars = vec![SCALAR_UNIT; nr_inp_params];
+
+    let grammar = match &search.constant_units {
+        Some(constant_units) => expr::UnitGrammar {
+            inputs: unit_inputs, constants: constant_units, output: unit_outputs[0] },
+        None => expr::UnitGrammar {
+            inputs: &scalars, constants: &[SCALAR_UNIT], output: SCALAR_UNIT },
+    };
+
+    let candidates = expr::dedupe(expr::enumerate_to_depth(&grammar, search.max_depth, search.max_size), nr_inp_params);
+
+    let mut exprs: Vec<ExprFit> = fit_candidates(candidates, nr_inp_params, inputs, outputs, ssigmas, config)?
+        .into_iter()
+        .map(|(expr, res)| ExprFit { expr, constants: res.constants, chi2: res.reduced_chi2, scores: res.scores })
+        .collect();
+
+    exprs.sort_by(|a, b| cmp_nan_last(a.scores.get(config.criterion), b.scores.get(config.criterion)));
+    exprs.truncate(search.max_results);
+
+    Ok(exprs)
+}
+
+/// Get Pareto-optimal candidates trading fit error for simplicity.
+///
+/// Both equations from `EQUATIONS` with matching units and, for single output,
+/// expression trees built as in [find_expression_with] are fitted.
+/// A candidate is kept only if every simpler candidate has larger χ²,
+/// so the front goes from simple rough laws to complex accurate ones,
+/// see [ParetoPoint].
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_mks::*;
+/// let inputs = [0.0, 1.0, 2.0, 3.0, 4.0];
+/// let outputs = [0.1, 3.4, 8.1, 13.4, 19.9];
+/// let front = find_pareto_front(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &[0.1], &ExprSearch::default());
+/// assert!(front.windows(2).all(|w| w[0].complexity < w[1].complexity && w[0].chi2 > w[1].chi2));
+/// ```
+pub fn find_pareto_front(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    search: &ExprSearch
+) -> Vec<ParetoPoint>
+{
+    let mut points: Vec<ParetoPoint> = find_equation_fits(unit_inputs, unit_outputs, inputs, outputs, ssigmas, &search.fit)
+        .into_iter()
+        .map(|(id, res)| ParetoPoint {
+            candidate: Candidate::Equation(id),
+            constants: res.constants,
+            chi2: res.chi2,
+            reduced_chi2: res.reduced_chi2,
+            complexity: res.scores.complexity,
+        })
+        .collect();
+
+    if unit_outputs.len() == 1 {
+        let exprs = expect_data(fit_expressions(unit_inputs, unit_outputs, inputs, outputs, ssigmas, search, &search.config()));
+        points.extend(exprs.into_iter().map(|(expr, res)| ParetoPoint {
+            candidate: Candidate::Expression(expr),
+            constants: res.constants,
+            chi2: res.chi2,
+            reduced_chi2: res.reduced_chi2,
+            complexity: res.scores.complexity,
+        }));
+    }
+
+    pareto::pareto_front(points)
+}
+
+/// Fit all enumerated expression trees, keep those with finite reduced χ².
+fn fit_expressions(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    search: &ExprSearch,
+    config: &RegressionConfig
+) -> Result<Vec<(Expr, FitResult)>>
+{
+    let nr_inp_params = unit_inputs.len();
+
+    let candidates = match &search.constant_units {
+        Some(constant_units) => {
+            let grammar = expr::UnitGrammar {
+                inputs: unit_inputs, constants: constant_units, output: unit_outputs[0] };
+            expr::enumerate_with_units(&grammar, search.max_size)
+        },
+        None => expr::enumerate(nr_inp_params, search.max_size),
+    };
+
+    fit_candidates(candidates, nr_inp_params, inputs, outputs, ssigmas, config)
+}
+
+/// Fit expression trees in order within budget, keep those with finite reduced χ².
+///
+/// Constants of every tree are fitted with options seeded from `config` by position of the tree.
+fn fit_candidates(
+    candidates: Vec<Expr>,
+    nr_inp_params: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    config: &RegressionConfig
+) -> Result<Vec<(Expr, FitResult)>>
+{
+    use std::rc::Rc;
+
+    // Expression trees have one output.
+    let nr_measurements = check_data(nr_inp_params, 1, inputs, outputs, ssigmas)?;
+
+    let candidates: Vec<(usize, Expr)> = candidates.into_iter()
+        .filter(|e| e.nr_constants() <= nr_measurements)
+        .enumerate()
+        .collect();
+
+    let fits: Vec<Result<Option<(Expr, FitResult)>>> = budget::map(&candidates, &config.budget, &config.fit.pool, |(index, e)| {
+        let model = expr::ExprModel { expr: Rc::new(e.clone()), nr_inp: nr_inp_params };
+        let res = fit_and_score(&model, inputs, outputs, ssigmas, &config.fit_options(*index))?;
+        Ok(res.reduced_chi2.is_finite().then(|| (e.clone(), res)))
+    }, |fit| fit.as_ref().ok().and_then(Option::as_ref).map_or(f64::NAN, |(_, res)| res.reduced_chi2));
+
+    let fits: Vec<Option<(Expr, FitResult)>> = fits.into_iter().collect::<Result<_>>()?;
+
+    Ok(fits.into_iter().flatten().collect())
 }
 
 /// Return Reduced χ² Chi-squared goodness-of_fit value.
@@ -74,6 +797,11 @@ pub fn find_equation(
 /// with M parameters which are set to some reasonable trial value.
 ///
 /// Note: when `ssigmas` input array lenght is 0, we **ASSUME** unweighted data when all sigma(i)=1.
+/// When `ssigmas` has one value per output parameter, same sigma is used for all measurements
+/// of that output, otherwise there must be a sigma for every output value.
+///
+/// Equations with several outputs sum χ² over all outputs,
+/// degrees of freedom are `N*nr_outputs - M`.
 ///
 /// William H. Press - Numerical recipes, the art of scientific computing.
 /// Cambridge University Press (2007):
@@ -87,53 +815,478 @@ pub fn find_equation(
 ///
 pub fn goodness_of_fit(id: usize, inputs: &[f64], outputs: &[f64], ssigmas: &[f64]) -> f64
 {
-    let equation_builder = &EQUATIONS[id];
-    let (out_params, cns_params, inp_params) = (equation_builder.params)();
-    let (nr_out_params, nr_cns_params, nr_inp_params) = (out_params.len(), cns_params.len(), inp_params.len());
+    goodness_of_fit_with(id, inputs, outputs, ssigmas, &FitOptions::default())
+}
+
+/// Return Reduced χ² goodness-of_fit value, fail on unknown equation or malformed data.
+///
+/// Same as [goodness_of_fit], but equation index and lengths of measured inputs,
+/// outputs and sigmas are checked instead of panicking.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{try_goodness_of_fit, Error, EQUATIONS};
+/// assert_eq!(try_goodness_of_fit(EQUATIONS.len(), &[1.0], &[1.0], &[]), Err(Error::NoEquation(EQUATIONS.len())));
+/// assert_eq!(try_goodness_of_fit(0, &[1.0, 2.0], &[1.0, 2.0], &[0.1, 0.1, 0.1]),
+///     Err(Error::SigmaLength { expected: 2, actual: 3 }));
+/// ```
+pub fn try_goodness_of_fit(
+    id: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64]
+) -> Result<f64>
+{
+    let equation = EQUATIONS.get(id).ok_or(Error::NoEquation(id))?;
+    Ok(fit_and_score(equation, inputs, outputs, ssigmas, &FitOptions::default())?.reduced_chi2)
+}
+
+/// Return Reduced χ² goodness-of_fit value, constants are fitted with provided options.
+///
+/// With robust `options.loss` χ² is replaced by robust deviance `∑ 2ρ((Oᵢ - fᵢ)/sigmaᵢ)`,
+/// see [Loss].
+pub fn goodness_of_fit_with(
+    id: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions
+) -> f64
+{
+    expect_data(fit_and_score(&EQUATIONS[id], inputs, outputs, ssigmas, options)).reduced_chi2
+}
+
+/// Result of fitting equation constants to measured values.
+#[derive(Debug, Clone)]
+pub struct FitResult {
+    /// Fitted constants
+    pub constants: Vec<f64>,
+    /// Standard errors of fitted constants, square roots of covariance diagonal
+    pub std_errors: Vec<f64>,
+    /// Covariance matrix of fitted constants, row-major `nr_constants x nr_constants`
+    pub covariance: Vec<f64>,
+    /// χ² of the fit, robust deviance `∑ 2ρ(rᵢ)` with robust loss
+    pub chi2: f64,
+    /// Degrees of freedom `N*nr_outputs - M`
+    pub degrees_of_freedom: usize,
+    /// Reduced χ², `chi2/degrees_of_freedom`
+    pub reduced_chi2: f64,
+    /// Probability of χ² this large or larger by chance if the model is correct,
+    /// meaningful only when sigmas of measurements are known
+    pub p_value: f64,
+    /// Scores of model selection criteria
+    pub scores: Scores,
+    /// Robust loss weight of every output value, from 0 to 1; empty for χ² loss
+    pub robust_weights: Vec<f64>,
+    /// Indices of measurements down-weighted by robust loss to less than half
+    pub down_weighted: Vec<usize>,
+}
+
+/// Fit equation constants and estimate their uncertainties.
+///
+/// Covariance matrix is `(JᵀWJ)⁻¹` where J is Jacobian of predicted values with respect to
+/// constants at the minimum and W is diagonal with weights `1/sigma(i)^2`.
+/// When `ssigmas` is empty, sigma is unknown and estimated from the fit,
+/// covariance is scaled by reduced χ².
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+/// let inputs = [0.0, 1.0, 2.0, 3.0, 4.0];
+/// let outputs = [3.1, 4.9, 7.0, 9.1, 10.9];
+/// let id = get_equation_by_typeid(VelocityEquation::params).unwrap();
+/// let res = fit_equation(id, &inputs, &outputs, &[0.1], &FitOptions::default());
+/// assert!((res.constants[1] - 2.0).abs() < 2.0 * res.std_errors[1]);
+/// assert!(res.p_value > 0.05);
+/// ```
+pub fn fit_equation(
+    id: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions
+) -> FitResult
+{
+    expect_data(fit_with_uncertainties(&EQUATIONS[id], inputs, outputs, ssigmas, options))
+}
+
+/// Get list of equations that fit to measured values with fitted constants and their uncertainties.
+///
+/// Same as [find_equation] but returns [FitResult] for every equation.
+pub fn find_equation_fits(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions
+) -> Vec<(usize, FitResult)>
+{
+    find_equation_ranked(unit_inputs, unit_outputs, inputs, outputs, ssigmas, options, Criterion::ReducedChi2)
+}
+
+/// Get list of equations that fit to measured values ranked by model selection criterion.
+///
+/// Same as [find_equation_fits] but sorted by the score of `criterion`,
+/// which penalizes number of constants and complexity of equations,
+/// all scores are reported in [FitResult::scores], see [Scores].
+pub fn find_equation_ranked(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions,
+    criterion: Criterion
+) -> Vec<(usize, FitResult)>
+{
+    let ids: Vec<usize> = find_equation_by_units(unit_inputs, unit_outputs);
+
+    let mut eqs: Vec<(usize, FitResult)> = pool::map(&ids, &options.pool, |id| {
+        (*id, fit_equation(*id, inputs, outputs, ssigmas, options))
+    });
+
+    eqs.sort_by(|a, b| cmp_nan_last(a.1.scores.get(criterion), b.1.scores.get(criterion)));
+
+    eqs
+}
+
+/// Get list of equations that fit to measured values, reproducible from seed.
+///
+/// Same as [find_equation_ranked], constants of every equation are fitted with
+/// options seeded from `config`, see [RegressionConfig].
+/// Equations are fitted in order of `EQUATIONS` within `config.budget`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_mks::*;
+/// let inputs = [0.0, 1.0, 2.0, 3.0];
+/// let outputs = [0.1, 3.4, 8.1, 13.4];
+/// let config = RegressionConfig { seed: 7, fit: FitOptions { starts: 4, ..FitOptions::default() }, ..RegressionConfig::default() };
+/// let eqs = find_equation_seeded(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &[0.1], &config);
+/// let again = find_equation_seeded(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &[0.1], &config);
+/// assert_eq!(eqs[0].1.constants, again[0].1.constants);
+/// ```
+pub fn find_equation_seeded(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    config: &RegressionConfig
+) -> Vec<(usize, FitResult)>
+{
+    let ids: Vec<usize> = find_equation_by_units(unit_inputs, unit_outputs);
+
+    let mut eqs: Vec<(usize, FitResult)> = budget::map(&ids, &config.budget, &config.fit.pool, |id| {
+        (*id, fit_equation(*id, inputs, outputs, ssigmas, &config.fit_options(*id)))
+    }, |(_, res)| res.reduced_chi2);
+
+    eqs.sort_by(|a, b| cmp_nan_last(a.1.scores.get(config.criterion), b.1.scores.get(config.criterion)));
+
+    eqs
+}
+
+/// Cross-validate equation, constants are fitted without validation measurements.
+///
+/// Unlike [goodness_of_fit], error is also measured on data not used to fit constants,
+/// so equations that merely follow noise get larger validation error.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+/// let inputs = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+/// let outputs = [3.1, 4.9, 7.0, 9.1, 10.9, 13.0, 15.1, 16.9];
+/// let id = get_equation_by_typeid(VelocityEquation::params).unwrap();
+/// let validation = Validation { mode: ValidationMode::KFold(4), seed: 1 };
+/// let score = validate_equation(id, &inputs, &outputs, &[0.1], &FitOptions::default(), &validation);
+/// assert!(score.train_error < score.validation_error);
+/// assert!(score.validation_error < 2.0);
+/// ```
+pub fn validate_equation(
+    id: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions,
+    validation: &Validation
+) -> ValidationScore
+{
+    expect_data(validate::cross_validate(&EQUATIONS[id], inputs, outputs, ssigmas, options, validation))
+}
+
+/// Get list of equations that fit to measured values sorted by validation error.
+///
+/// Same as [find_equation_fits] but every equation is cross-validated, see [validate_equation].
+pub fn find_equation_validated(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions,
+    validation: &Validation
+) -> Vec<(usize, ValidationScore)>
+{
+    let ids: Vec<usize> = find_equation_by_units(unit_inputs, unit_outputs);
+
+    let mut eqs: Vec<(usize, ValidationScore)> = pool::map(&ids, &options.pool, |id| {
+        (*id, validate_equation(*id, inputs, outputs, ssigmas, options, validation))
+    });
+
+    eqs.sort_by(|a, b| cmp_nan_last(a.1.validation_error, b.1.validation_error));
+
+    eqs
+}
+
+/// Cross-validate equation, reproducible from seed.
+///
+/// Same as [validate_equation], but constants are fitted with options of candidate `id`
+/// and measurements are split with seed derived from `config`,
+/// see [RegressionConfig::fit_options] and [RegressionConfig::validation].
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::*;
+/// use rustamath_physics::mechanics::linear_motion::const_accel::VelocityEquation;
+/// let inputs = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+/// let outputs = [3.1, 4.9, 7.0, 9.1, 10.9, 13.0, 15.1, 16.9];
+/// let id = get_equation_by_typeid(VelocityEquation::params).unwrap();
+/// let config = RegressionConfig { seed: 1, ..RegressionConfig::default() };
+/// let score = validate_equation_seeded(id, &inputs, &outputs, &[0.1], ValidationMode::KFold(4), &config);
+/// assert!(score.validation_error < 2.0);
+/// ```
+pub fn validate_equation_seeded(
+    id: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    mode: ValidationMode,
+    config: &RegressionConfig
+) -> ValidationScore
+{
+    expect_data(validate::cross_validate(&EQUATIONS[id], inputs, outputs, ssigmas,
+        &config.fit_options(id), &config.validation(mode)))
+}
+
+/// Get list of equations sorted by validation error, reproducible from seed.
+///
+/// Same as [find_equation_validated], every equation is cross-validated with
+/// [validate_equation_seeded] in order of `EQUATIONS` within `config.budget`.
+pub fn find_equation_validated_seeded(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    mode: ValidationMode,
+    config: &RegressionConfig
+) -> Vec<(usize, ValidationScore)>
+{
+    let ids: Vec<usize> = find_equation_by_units(unit_inputs, unit_outputs);
+
+    let mut eqs: Vec<(usize, ValidationScore)> = budget::map(&ids, &config.budget, &config.fit.pool, |id| {
+        (*id, validate_equation_seeded(*id, inputs, outputs, ssigmas, mode, config))
+    }, |(_, score)| score.validation_error);
+
+    eqs.sort_by(|a, b| cmp_nan_last(a.1.validation_error, b.1.validation_error));
+
+    eqs
+}
 
-    assert!(ssigmas.is_empty() || ssigmas.len() == outputs.len());
+/// Fit model constants and estimate their covariance matrix.
+fn fit_with_uncertainties(
+    model: &dyn Model,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions
+) -> Result<FitResult>
+{
+    let mut res = fit_and_score(model, inputs, outputs, ssigmas, options)?;
 
+    let (nr_out_params, nr_cns_params, nr_inp_params) = model.dims();
     let nr_measurements = inputs.len() / nr_inp_params;
-    assert_eq!(outputs.len() / nr_out_params, nr_measurements);
 
-    let mut equation_constants: Vec<f64> = Vec::new();
-    equation_constants.resize(nr_cns_params, 1.0);
+    if nr_cns_params == 0 {
+        return Ok(res);
+    }
 
-    if nr_cns_params > 0 && nr_measurements >= nr_cns_params {
-        // Find constant parameters of the equation
-        fit::fit(equation_builder, inputs, outputs, &mut equation_constants,
-            nr_measurements, nr_inp_params);
+    let jac = fit::jacobian(model, inputs, &res.constants, nr_measurements, nr_inp_params);
+
+    // Curvature matrix JᵀWJ
+    let mut alpha = vec![0.0_f64; nr_cns_params * nr_cns_params];
+    for k in 0..nr_measurements * nr_out_params {
+        let row = &jac[k * nr_cns_params..(k + 1) * nr_cns_params];
+        let sigma = sigma(ssigmas, nr_out_params, k);
+        let weight = res.robust_weights.get(k).unwrap_or(&1.0) / (sigma * sigma);
+        for j in 0..nr_cns_params {
+            for l in 0..nr_cns_params {
+                alpha[j * nr_cns_params + l] += row[j] * row[l] * weight;
+            }
+        }
     }
 
-    let mut equation = (equation_builder.new)(&equation_constants);
+    let mut covariance = linalg::invert(&alpha, nr_cns_params)
+        .unwrap_or_else(|| vec![f64::NAN; nr_cns_params * nr_cns_params]);
 
-    let mut predictions: Vec<f64> = Vec::with_capacity(outputs.len());
+    if ssigmas.is_empty() {
+        // Unknown sigma estimated as sigma^2=∑(Oᵢ - fᵢ)²/(N-M).
+        covariance.iter_mut().for_each(|c| *c *= res.reduced_chi2);
+    }
 
-    for i in 0..nr_measurements {
-        let input_start_index = i * nr_inp_params;
-        let input_end_index = input_start_index + nr_inp_params;
-        let mut prediction = equation.run(&inputs[input_start_index..input_end_index]);
-        predictions.append(&mut prediction);
+    res.std_errors = (0..nr_cns_params).map(|j| covariance[j * nr_cns_params + j].sqrt()).collect();
+    res.covariance = covariance;
+
+    Ok(res)
+}
+
+/// Fit model constants and return them with χ² of the fit, fail on malformed data.
+fn fit_and_score(
+    model: &dyn Model,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions
+) -> Result<FitResult>
+{
+    let (nr_out_params, nr_cns_params, nr_inp_params) = model.dims();
+
+    let nr_measurements = check_data(nr_inp_params, nr_out_params, inputs, outputs, ssigmas)?;
+
+    let nr_data_points = nr_measurements * nr_out_params;
+
+    let mut equation_constants: Vec<f64> = Vec::new();
+    equation_constants.resize(nr_cns_params, 1.0);
+
+    // Start from the equation's own estimate unless caller provides initial guess.
+    if options.initial.is_none() {
+        if let Some(guess) = model.guess(inputs, outputs) {
+            if guess.len() == nr_cns_params && guess.iter().all(|c| c.is_finite()) {
+                equation_constants = guess;
+            }
+        }
+    }
+
+    if nr_cns_params > 0 && nr_data_points >= nr_cns_params {
+        // Find constant parameters of the equation
+        fit::fit(model, inputs, outputs, ssigmas, &mut equation_constants,
+            nr_measurements, nr_inp_params, options);
     }
 
-    assert!(nr_out_params == 1);//FIXME !!! XXX !!!
+    let mut predictions: Vec<f64> = vec![0.0; nr_measurements * nr_out_params];
+    fit::predict_into(model, &inputs[..nr_measurements * nr_inp_params], &equation_constants, &mut predictions);
 
     let mut chi2: f64 = 0.0_f64;
+    let mut robust_weights: Vec<f64> = Vec::new();
+    let mut down_weighted: Vec<usize> = Vec::new();
 
     for i in 0..nr_measurements {
         let output_start_index = i * nr_out_params;
         //let output_end_index = output_start_index + nr_out_params;
         for j in 0..nr_out_params {
             let diff = outputs[output_start_index + j] - predictions[output_start_index + j];
-            let sigma = if ssigmas.is_empty() { 1.0 } else { ssigmas[output_start_index + j] };
-            chi2 += (diff * diff) / (sigma * sigma);
+            let sigma = sigma(ssigmas, nr_out_params, output_start_index + j);
+            chi2 += options.loss.deviance(diff / sigma);
+            if options.loss != Loss::Chi2 {
+                let weight = options.loss.weight(diff / sigma).min(1.0);
+                if weight < DOWN_WEIGHT && down_weighted.last() != Some(&i) {
+                    down_weighted.push(i);
+                }
+                robust_weights.push(weight);
+            }
         }
     }
 
-    let degrees_of_freedom = if nr_measurements > nr_cns_params { nr_measurements - nr_cns_params } else { 1 };
+    let degrees_of_freedom = if nr_data_points > nr_cns_params { nr_data_points - nr_cns_params } else { 1 };
+    let reduced_chi2 = chi2 / degrees_of_freedom as f64;
+
+    Ok(FitResult {
+        constants: equation_constants,
+        std_errors: Vec::new(),
+        covariance: Vec::new(),
+        chi2,
+        degrees_of_freedom,
+        reduced_chi2,
+        p_value: stats::chi2_p_value(chi2, degrees_of_freedom),
+        scores: Scores::new(chi2, reduced_chi2, nr_data_points, nr_cns_params, model.complexity(), !ssigmas.is_empty()),
+        robust_weights,
+        down_weighted,
+    })
+}
+
+/// Check lengths of measured data, return number of measurements.
+fn check_data(
+    nr_inp_params: usize,
+    nr_out_params: usize,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64]
+) -> Result<usize>
+{
+    let data_error = Error::DataLength {
+        nr_inputs: nr_inp_params, nr_outputs: nr_out_params,
+        inputs_len: inputs.len(), outputs_len: outputs.len() };
+
+    if nr_inp_params == 0 || nr_out_params == 0
+        || !inputs.len().is_multiple_of(nr_inp_params) || !outputs.len().is_multiple_of(nr_out_params)
+        || inputs.len() / nr_inp_params != outputs.len() / nr_out_params
+    {
+        return Err(data_error);
+    }
+
+    if !(ssigmas.is_empty() || ssigmas.len() == nr_out_params || ssigmas.len() == outputs.len()) {
+        return Err(Error::SigmaLength { expected: outputs.len(), actual: ssigmas.len() });
+    }
+
+    Ok(inputs.len() / nr_inp_params)
+}
+
+/// Check data of expression search, which takes exactly one output unit.
+fn check_expression_data(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64]
+) -> Result<usize>
+{
+    if unit_outputs.len() != 1 {
+        return Err(Error::OutputUnitCount { expected: 1, actual: unit_outputs.len() });
+    }
+    check_data(unit_inputs.len(), unit_outputs.len(), inputs, outputs, ssigmas)
+}
+
+/// Value of regression on well-formed data, panic with the error otherwise.
+fn expect_data<T>(res: Result<T>) -> T {
+    res.unwrap_or_else(|err| panic!("{}", err))
+}
+
+/// Order scores ascending with NaN last.
+fn cmp_nan_last(a: f64, b: f64) -> std::cmp::Ordering {
+    match (a.is_nan(), b.is_nan()) {
+        (false, false) => a.total_cmp(&b),
+        (a_nan, b_nan) => a_nan.cmp(&b_nan),
+    }
+}
 
-    // Reduced chi2
-    chi2 /= degrees_of_freedom as f64;
+/// Measurement with robust loss weight below this is reported as down-weighted.
+const DOWN_WEIGHT: f64 = 0.5;
 
-    chi2
-}
\ No newline at end of file
+/// Sigma of output value at `index`, sigmas are either empty, per output parameter or per value.
+#[inline]
+fn sigma(ssigmas: &[f64], nr_out_params: usize, index: usize) -> f64 {
+    match ssigmas.len() {
+        0 => 1.0,
+        n if n == nr_out_params => ssigmas[index % nr_out_params],
+        _ => ssigmas[index],
+    }
+}
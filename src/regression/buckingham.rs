@@ -0,0 +1,269 @@
+//! Buckingham-Π dimensional analysis.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Buckingham_%CF%80_theorem>
+//! - [AI Feynman: a physics-inspired method for symbolic regression](https://arxiv.org/pdf/1905.11481.pdf)
+//!
+//! Physical law between `n` variables with units of rank `r` can be written
+//! as a law between `n - r` dimensionless products of powers of the variables.
+//! Powers of every product are a vector in the null space of the exponent matrix,
+//! which column `j` holds powers of meter, kilogram, second and ampere of variable `j`.
+use rustamath_mks::*;
+use super::super::{Error, Result};
+use super::expr::Expr;
+use super::units::{unit_exponents, MAX_EXPONENT};
+
+/// Dimensionless product of variables raised to integer powers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiGroup {
+    /// Power of every variable
+    pub exponents: Vec<i64>,
+}
+
+impl PiGroup {
+    /// Value of the group for given values of variables.
+    pub fn value(&self, values: &[f64]) -> f64 {
+        self.exponents.iter().zip(values.iter())
+            .filter(|(e, _)| **e != 0)
+            .map(|(e, v)| v.powi(*e as i32))
+            .product()
+    }
+
+    /// Numerator and denominator of the group as products of `x0, x1, ...`,
+    /// `None` if empty.
+    fn fraction(&self) -> (Option<Expr>, Option<Expr>) {
+        let product = |sign: i64| {
+            self.exponents.iter().enumerate()
+                .flat_map(|(i, e)| std::iter::repeat_n(i, (e * sign).max(0) as usize))
+                .map(Expr::Input)
+                .reduce(|acc, x| Expr::Mul(Box::new(acc), Box::new(x)))
+        };
+        (product(1), product(-1))
+    }
+}
+
+/// Basis of dimensionless groups of variables with given units.
+///
+/// Every group has integer powers with no common divisor.
+///
+/// # Panics
+///
+/// If power of meter, kilogram, second or ampere in any unit is beyond 8.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::pi_groups;
+/// use rustamath_mks::*;
+/// // Pendulum length, gravity and period: `g*T^2/L`
+/// let groups = pi_groups(&[DISTANCE_UNIT, ACCEL_UNIT, TIME_UNIT]);
+/// assert_eq!(groups.len(), 1);
+/// assert_eq!(groups[0].exponents, vec![-1, 1, 2]);
+/// ```
+pub fn pi_groups(units: &[MksUnit]) -> Vec<PiGroup> {
+    let exponents: Vec<[i64; 4]> = units.iter()
+        .map(|u| unit_exponents(*u).unwrap_or_else(|| panic!("unit {} has power beyond {}", u, MAX_EXPONENT)))
+        .collect();
+    let matrix: Vec<Vec<i64>> = (0..4)
+        .map(|dim| exponents.iter().map(|e| e[dim]).collect())
+        .collect();
+
+    null_space(matrix, units.len()).into_iter()
+        .map(|exponents| PiGroup { exponents })
+        .collect()
+}
+
+/// Regression problem rewritten in dimensionless groups.
+///
+/// Inputs are replaced by groups of inputs only, output by the only group
+/// with output `y^p * x...`, so the law becomes `output group = f(input groups)`
+/// with fewer unit-less variables.
+#[derive(Debug, Clone)]
+pub struct DimensionlessProblem {
+    /// Groups of inputs, powers of inputs
+    pub inputs: Vec<PiGroup>,
+    /// Group with output, powers of inputs followed by power of output
+    pub output: PiGroup,
+}
+
+impl DimensionlessProblem {
+    /// Find dimensionless groups for inputs and output.
+    ///
+    /// Fail if output unit is not a product of powers of input units,
+    /// then the law needs a dimensional constant.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{DimensionlessProblem, Error};
+    /// use rustamath_mks::*;
+    /// // Distance from initial velocity, time and acceleration
+    /// let problem = DimensionlessProblem::new(&[VELOCITY_UNIT, TIME_UNIT, ACCEL_UNIT], DISTANCE_UNIT).unwrap();
+    /// assert_eq!(problem.inputs.len(), 1);
+    /// assert_eq!(problem.output.exponents, vec![-1, -1, 0, 1]);
+    /// assert_eq!(DimensionlessProblem::new(&[TIME_UNIT], DISTANCE_UNIT).err(), Some(Error::NotDimensionless));
+    /// ```
+    pub fn new(unit_inputs: &[MksUnit], unit_output: MksUnit) -> Result<Self> {
+        let units: Vec<MksUnit> = unit_inputs.iter().copied().chain(std::iter::once(unit_output)).collect();
+        let nr_inputs = unit_inputs.len();
+
+        let (with_output, inputs): (Vec<PiGroup>, Vec<PiGroup>) = pi_groups(&units).into_iter()
+            .partition(|g| g.exponents[nr_inputs] != 0);
+
+        // Output column is last, so it is either free and in one group or in none.
+        let output = with_output.into_iter().next().ok_or(Error::NotDimensionless)?;
+        let inputs = inputs.into_iter()
+            .map(|mut g| { g.exponents.truncate(nr_inputs); g })
+            .collect();
+
+        Ok(DimensionlessProblem { inputs, output })
+    }
+
+    /// Values of input groups and output group for every measurement.
+    pub fn transform(&self, inputs: &[f64], outputs: &[f64]) -> (Vec<f64>, Vec<f64>) {
+        let nr_inputs = self.output.exponents.len() - 1;
+        let mut pi_inputs: Vec<f64> = Vec::with_capacity(outputs.len() * self.inputs.len());
+        let mut pi_outputs: Vec<f64> = Vec::with_capacity(outputs.len());
+
+        for (x, y) in inputs.chunks(nr_inputs).zip(outputs.iter()) {
+            pi_inputs.extend(self.inputs.iter().map(|g| g.value(x)));
+            let variables: Vec<f64> = x.iter().copied().chain(std::iter::once(*y)).collect();
+            pi_outputs.push(self.output.value(&variables));
+        }
+
+        (pi_inputs, pi_outputs)
+    }
+
+    /// Output in physical units from physical inputs and value of the output group.
+    pub fn output_value(&self, inputs: &[f64], pi_output: f64) -> f64 {
+        let nr_inputs = self.output.exponents.len() - 1;
+        let power = self.output.exponents[nr_inputs];
+        let rest = PiGroup { exponents: self.output.exponents[..nr_inputs].to_vec() }.value(inputs);
+        let y = pi_output / rest;
+        if power == 1 { y } else { y.powf(1.0 / power as f64) }
+    }
+
+    /// Law `output group = expr(input groups)` rewritten as output of physical inputs.
+    ///
+    /// Constants keep their indices. `None` if power of output in its group
+    /// is not a power of 2, such root can not be taken in [Expr].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::{DimensionlessProblem, Expr};
+    /// use rustamath_mks::*;
+    /// let problem = DimensionlessProblem::new(&[VELOCITY_UNIT, TIME_UNIT, ACCEL_UNIT], DISTANCE_UNIT).unwrap();
+    /// let law: Expr = "c0 + c1*x0".parse().unwrap();
+    /// assert_eq!(problem.to_physical(&law).unwrap().to_string(), "(c0 + c1*(x1*x2/x0))*(x0*x1)");
+    /// ```
+    pub fn to_physical(&self, expr: &Expr) -> Option<Expr> {
+        let nr_inputs = self.output.exponents.len() - 1;
+        let mut power = self.output.exponents[nr_inputs];
+
+        let mut law = self.substitute(expr);
+
+        // Output group is `y^p * numerator / denominator`
+        let rest = PiGroup { exponents: self.output.exponents[..nr_inputs].to_vec() };
+        let (numerator, denominator) = rest.fraction();
+        if let Some(denominator) = denominator {
+            law = Expr::Mul(Box::new(law), Box::new(denominator));
+        }
+        if let Some(numerator) = numerator {
+            law = Expr::Div(Box::new(law), Box::new(numerator));
+        }
+
+        while power > 1 {
+            if power % 2 != 0 {
+                return None;
+            }
+            law = Expr::Sqrt(Box::new(law));
+            power /= 2;
+        }
+
+        Some(law)
+    }
+
+    /// Replace every input of dimensionless law with its group of physical inputs.
+    fn substitute(&self, expr: &Expr) -> Expr {
+        let sub = |e: &Expr| Box::new(self.substitute(e));
+        match expr {
+            Expr::Const(_) => expr.clone(),
+            Expr::Input(i) => match self.inputs[*i].fraction() {
+                (Some(n), Some(d)) => Expr::Div(Box::new(n), Box::new(d)),
+                (Some(n), None) => n,
+                // Free input of the group always has positive power
+                (None, _) => unreachable!(),
+            },
+            Expr::Add(a, b) => Expr::Add(sub(a), sub(b)),
+            Expr::Sub(a, b) => Expr::Sub(sub(a), sub(b)),
+            Expr::Mul(a, b) => Expr::Mul(sub(a), sub(b)),
+            Expr::Div(a, b) => Expr::Div(sub(a), sub(b)),
+            Expr::Pow(a, b) => Expr::Pow(sub(a), sub(b)),
+            Expr::Sin(a) => Expr::Sin(sub(a)),
+            Expr::Exp(a) => Expr::Exp(sub(a)),
+            Expr::Log(a) => Expr::Log(sub(a)),
+            Expr::Sqrt(a) => Expr::Sqrt(sub(a)),
+        }
+    }
+}
+
+/// Integer basis of null space of `rows x nr_cols` matrix, one vector per free column.
+///
+/// Fraction-free Gauss-Jordan elimination keeps all entries integer;
+/// vector of free column `f` has positive power at `f`.
+fn null_space(mut rows: Vec<Vec<i64>>, nr_cols: usize) -> Vec<Vec<i64>> {
+    let mut pivots: Vec<usize> = Vec::new();
+
+    for col in 0..nr_cols {
+        let r = pivots.len();
+        if r == rows.len() {
+            break;
+        }
+        let Some(p) = (r..rows.len()).find(|i| rows[*i][col] != 0) else { continue };
+        rows.swap(r, p);
+        for i in 0..rows.len() {
+            if i != r && rows[i][col] != 0 {
+                let (a, b) = (rows[r][col], rows[i][col]);
+                let pivot_row = rows[r].clone();
+                for (x, p) in rows[i].iter_mut().zip(pivot_row.iter()) {
+                    *x = *x * a - p * b;
+                }
+                reduce(&mut rows[i]);
+            }
+        }
+        pivots.push(col);
+    }
+
+    let multiple = pivots.iter().enumerate().fold(1, |l, (r, c)| lcm(l, rows[r][*c].abs()));
+
+    (0..nr_cols).filter(|c| !pivots.contains(c)).map(|free| {
+        let mut v = vec![0_i64; nr_cols];
+        v[free] = multiple;
+        for (r, c) in pivots.iter().enumerate() {
+            v[*c] = -multiple * rows[r][free] / rows[r][*c];
+        }
+        reduce(&mut v);
+        v
+    }).collect()
+}
+
+/// Divide vector by greatest common divisor of its entries.
+fn reduce(v: &mut [i64]) {
+    let divisor = v.iter().fold(0, |g, x| gcd(g, x.abs()));
+    if divisor > 1 {
+        v.iter_mut().for_each(|x| *x /= divisor);
+    }
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+    a / gcd(a, b) * b
+}
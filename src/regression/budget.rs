@@ -0,0 +1,60 @@
+//! Time and evaluation budget of regression search.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use super::pool::{self, PoolOptions};
+
+/// Limits of regression search, results found so far are returned when a limit is hit.
+///
+/// Evaluation budget fits only the first candidates in their fixed order,
+/// so it keeps results reproducible; wall-clock budget and early stopping
+/// depend on speed and scheduling of worker threads.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+/// use rustamath_physics::{Budget, ExprSearch};
+/// let budget = Budget { time: Some(Duration::from_secs(10)), target_reduced_chi2: Some(1.0), ..Budget::default() };
+/// let search = ExprSearch { max_size: 7, budget, ..ExprSearch::default() };
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Budget {
+    /// Wall-clock time limit
+    pub time: Option<Duration>,
+    /// Maximum number of fitted candidates
+    pub evaluations: Option<usize>,
+    /// Stop once a candidate fits with reduced χ² at or below this
+    pub target_reduced_chi2: Option<f64>,
+}
+
+/// Fit candidates on worker pool within budget, return results of fitted candidates in order.
+pub fn map<T, R, F, S>(items: &[T], budget: &Budget, options: &PoolOptions, fun: F, reduced_chi2: S) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    S: Fn(&R) -> f64 + Sync,
+{
+    let start = Instant::now();
+    let stop = AtomicBool::new(false);
+
+    let items = &items[..budget.evaluations.map_or(items.len(), |n| n.min(items.len()))];
+
+    let results: Vec<Option<R>> = pool::map(items, options, |item| {
+        let out_of_time = budget.time.is_some_and(|time| start.elapsed() >= time);
+        if out_of_time || stop.load(Ordering::Relaxed) {
+            return None;
+        }
+        let result = fun(item);
+        if budget.target_reduced_chi2.is_some_and(|target| reduced_chi2(&result) <= target) {
+            stop.store(true, Ordering::Relaxed);
+        }
+        Some(result)
+    });
+
+    results.into_iter().flatten().collect()
+}
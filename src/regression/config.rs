@@ -0,0 +1,62 @@
+//! Reproducible configuration of regression runs.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+use super::budget::Budget;
+use super::criteria::Criterion;
+use super::fit::FitOptions;
+use super::rng;
+use super::validate::{Validation, ValidationMode};
+
+/// Random stream of cross-validation folds, candidates use their index as stream.
+const VALIDATION_STREAM: u64 = u64::MAX;
+
+/// Configuration of regression run, the seed drives every random choice.
+///
+/// Each candidate gets its own random stream derived from the seed and
+/// candidate index, and candidates are ranked in a fixed order, so same seed
+/// gives bit-identical results regardless of number of threads and their scheduling.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{FitOptions, RegressionConfig};
+/// let config = RegressionConfig { seed: 42, fit: FitOptions { starts: 8, ..FitOptions::default() }, ..RegressionConfig::default() };
+/// assert_eq!(config.fit_options(3).seed, config.fit_options(3).seed);
+/// assert_ne!(config.fit_options(3).seed, config.fit_options(4).seed);
+/// ```
+#[derive(Debug, Clone)]
+pub struct RegressionConfig {
+    /// Seed of all random choices
+    pub seed: u64,
+    /// Options of fitting constants, `fit.seed` is replaced by seed of the candidate
+    pub fit: FitOptions,
+    /// Criterion to rank candidates
+    pub criterion: Criterion,
+    /// Limits of the search
+    pub budget: Budget,
+}
+
+impl Default for RegressionConfig {
+    fn default() -> Self {
+        RegressionConfig {
+            seed: 0,
+            fit: FitOptions::default(),
+            criterion: Criterion::ReducedChi2,
+            budget: Budget::default(),
+        }
+    }
+}
+
+impl RegressionConfig {
+    /// Fitting options of candidate with given index.
+    pub fn fit_options(&self, candidate: usize) -> FitOptions {
+        FitOptions { seed: rng::derive_seed(self.seed, candidate as u64), ..self.fit.clone() }
+    }
+
+    /// Cross-validation split seeded from configuration.
+    pub fn validation(&self, mode: ValidationMode) -> Validation {
+        Validation { mode, seed: rng::derive_seed(self.seed, VALIDATION_STREAM) }
+    }
+}
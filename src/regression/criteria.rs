@@ -0,0 +1,113 @@
+//! Model selection criteria penalizing number of constants and complexity.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Akaike_information_criterion>
+//! - <https://en.wikipedia.org/wiki/Bayesian_information_criterion>
+//! - <https://en.wikipedia.org/wiki/Minimum_description_length>
+//!
+
+/// Number of symbols each expression tree node is chosen from, in MDL code length.
+const NR_SYMBOLS: f64 = 16.0;
+
+/// Criterion to rank candidate equations, smaller score is better.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Criterion {
+    /// Reduced χ², no penalty beyond degrees of freedom
+    ReducedChi2,
+    /// Akaike information criterion
+    Aic,
+    /// Akaike information criterion corrected for small number of measurements
+    Aicc,
+    /// Bayesian (Schwarz) information criterion
+    Bic,
+    /// Minimum description length of constants, expression tree and residuals
+    Mdl,
+}
+
+/// Scores of a fitted candidate under all criteria.
+///
+/// Misfit term is χ² when sigmas of measurements are known, otherwise
+/// `N*ln(χ²/N)` of Gaussian likelihood with estimated variance,
+/// where N is number of measured values.
+/// With k fitted constants:
+///
+/// - AIC = misfit + 2k
+/// - AICc = AIC + 2k(k+1)/(N-k-1)
+/// - BIC = misfit + k*ln(N)
+/// - MDL = misfit/2 + (k/2)*ln(N) + complexity*ln(16), in nats
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Scores {
+    /// Reduced χ²
+    pub reduced_chi2: f64,
+    /// Akaike information criterion
+    pub aic: f64,
+    /// Corrected Akaike information criterion
+    pub aicc: f64,
+    /// Bayesian information criterion
+    pub bic: f64,
+    /// Minimum description length
+    pub mdl: f64,
+    /// Number of nodes in expression tree of the candidate
+    pub complexity: usize,
+}
+
+impl Scores {
+    /// Compute all scores of a fit.
+    pub(crate) fn new(
+        chi2: f64,
+        reduced_chi2: f64,
+        nr_values: usize,
+        nr_constants: usize,
+        complexity: usize,
+        weighted: bool
+    ) -> Self
+    {
+        let n = nr_values as f64;
+        let k = nr_constants as f64;
+
+        let misfit = if weighted {
+            chi2
+        } else {
+            n * (chi2 / n).max(f64::MIN_POSITIVE).ln()
+        };
+
+        let aic = misfit + 2.0 * k;
+        let aicc = if nr_values > nr_constants + 1 {
+            aic + 2.0 * k * (k + 1.0) / (n - k - 1.0)
+        } else {
+            f64::INFINITY
+        };
+        let bic = misfit + k * n.ln();
+        let mdl = misfit / 2.0 + k / 2.0 * n.ln() + complexity as f64 * NR_SYMBOLS.ln();
+
+        Scores { reduced_chi2, aic, aicc, bic, mdl, complexity }
+    }
+
+    /// Score under given criterion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::*;
+    /// use rustamath_mks::*;
+    /// let inputs = [0.0, 1.0, 2.0, 3.0, 4.0];
+    /// let outputs = [0.0, 3.0, 6.1, 8.9, 12.0];
+    /// let eqs = find_equation_ranked(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs,
+    ///     &[0.1], &FitOptions::default(), Criterion::Bic);
+    /// assert_eq!(EQUATIONS[eqs[0].0].desc, "Linear motion const velocity distance `s = v*t`");
+    /// assert!(eqs[0].1.scores.get(Criterion::Bic) <= eqs[1].1.scores.bic);
+    /// ```
+    pub fn get(&self, criterion: Criterion) -> f64 {
+        match criterion {
+            Criterion::ReducedChi2 => self.reduced_chi2,
+            Criterion::Aic => self.aic,
+            Criterion::Aicc => self.aicc,
+            Criterion::Bic => self.bic,
+            Criterion::Mdl => self.mdl,
+        }
+    }
+}
@@ -0,0 +1,786 @@
+//! Expression trees built from primitives.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! Candidate formulas are trees of operations (+, −, ×, ÷, pow, sin, exp, log, sqrt)
+//! with input parameters and fitted constants as leaves.
+//!
+//! When units of inputs and constants are known, only dimensionally consistent trees
+//! are built, see "Deep symbolic regression for physics guided by units constraints":
+//! addition only between like units, transcendental functions only on unit-less arguments
+//! and root unit matching the output unit.
+use std::collections::HashSet;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+use rustamath_mks::*;
+use super::super::{Equation, Error, Result};
+use super::Model;
+use super::units::unit_sqrt;
+
+/// Expression tree node.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    /// Constant parameter, index into array of fitted constants
+    Const(usize),
+    /// Input parameter, index into array of inputs
+    Input(usize),
+    /// `a + b`
+    Add(Box<Expr>, Box<Expr>),
+    /// `a - b`
+    Sub(Box<Expr>, Box<Expr>),
+    /// `a * b`
+    Mul(Box<Expr>, Box<Expr>),
+    /// `a / b`
+    Div(Box<Expr>, Box<Expr>),
+    /// `a^b`
+    Pow(Box<Expr>, Box<Expr>),
+    /// `sin(a)`
+    Sin(Box<Expr>),
+    /// `exp(a)`
+    Exp(Box<Expr>),
+    /// `log(a)`, natural logarithm
+    Log(Box<Expr>),
+    /// `sqrt(a)`
+    Sqrt(Box<Expr>),
+}
+
+impl Expr {
+    /// Evaluate expression with provided inputs and constants.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::Expr;
+    /// // `c0*x0 + c1`
+    /// let expr = Expr::Add(
+    ///     Box::new(Expr::Mul(Box::new(Expr::Const(0)), Box::new(Expr::Input(0)))),
+    ///     Box::new(Expr::Const(1)));
+    /// assert_eq!(expr.eval(&[3.0], &[2.0, 1.0]), 7.0);
+    /// assert_eq!(expr.to_string(), "c0*x0 + c1");
+    /// ```
+    pub fn eval(&self, inp: &[f64], cns: &[f64]) -> f64 {
+        match self {
+            Expr::Const(i) => cns[*i],
+            Expr::Input(i) => inp[*i],
+            Expr::Add(a, b) => a.eval(inp, cns) + b.eval(inp, cns),
+            Expr::Sub(a, b) => a.eval(inp, cns) - b.eval(inp, cns),
+            Expr::Mul(a, b) => a.eval(inp, cns) * b.eval(inp, cns),
+            Expr::Div(a, b) => a.eval(inp, cns) / b.eval(inp, cns),
+            Expr::Pow(a, b) => a.eval(inp, cns).powf(b.eval(inp, cns)),
+            Expr::Sin(a) => a.eval(inp, cns).sin(),
+            Expr::Exp(a) => a.eval(inp, cns).exp(),
+            Expr::Log(a) => a.eval(inp, cns).ln(),
+            Expr::Sqrt(a) => a.eval(inp, cns).sqrt(),
+        }
+    }
+
+    /// Partial derivative with respect to constant `c_k`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::Expr;
+    /// // `c0*x0 + c1`
+    /// let expr = Expr::Add(
+    ///     Box::new(Expr::Mul(Box::new(Expr::Const(0)), Box::new(Expr::Input(0)))),
+    ///     Box::new(Expr::Const(1)));
+    /// assert_eq!(expr.derivative(&[3.0], &[2.0, 1.0], 0), 3.0);
+    /// assert_eq!(expr.derivative(&[3.0], &[2.0, 1.0], 1), 1.0);
+    /// ```
+    pub fn derivative(&self, inp: &[f64], cns: &[f64], k: usize) -> f64 {
+        let d = |e: &Expr| e.derivative(inp, cns, k);
+        let v = |e: &Expr| e.eval(inp, cns);
+        match self {
+            Expr::Const(i) => if *i == k { 1.0 } else { 0.0 },
+            Expr::Input(_) => 0.0,
+            Expr::Add(a, b) => d(a) + d(b),
+            Expr::Sub(a, b) => d(a) - d(b),
+            Expr::Mul(a, b) => d(a) * v(b) + v(a) * d(b),
+            Expr::Div(a, b) => (d(a) * v(b) - v(a) * d(b)) / (v(b) * v(b)),
+            Expr::Pow(a, b) => {
+                let (va, vb, da, db) = (v(a), v(b), d(a), d(b));
+                let d_base = if da == 0.0 { 0.0 } else { vb * va.powf(vb - 1.0) * da };
+                let d_exponent = if db == 0.0 { 0.0 } else { va.powf(vb) * va.ln() * db };
+                d_base + d_exponent
+            },
+            Expr::Sin(a) => v(a).cos() * d(a),
+            Expr::Exp(a) => v(a).exp() * d(a),
+            Expr::Log(a) => d(a) / v(a),
+            Expr::Sqrt(a) => d(a) / (2.0 * v(a).sqrt()),
+        }
+    }
+
+    /// Number of nodes in the tree.
+    pub fn size(&self) -> usize {
+        match self {
+            Expr::Const(_) | Expr::Input(_) => 1,
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => 1 + a.size() + b.size(),
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => 1 + a.size(),
+        }
+    }
+
+    /// Number of levels in the tree, a leaf has depth 1.
+    pub fn depth(&self) -> usize {
+        match self {
+            Expr::Const(_) | Expr::Input(_) => 1,
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => 1 + a.depth().max(b.depth()),
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => 1 + a.depth(),
+        }
+    }
+
+    /// Number of constants to fit.
+    pub fn nr_constants(&self) -> usize {
+        match self {
+            Expr::Const(i) => i + 1,
+            Expr::Input(_) => 0,
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => a.nr_constants().max(b.nr_constants()),
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => a.nr_constants(),
+        }
+    }
+
+    /// True if expression depends on at least one input.
+    pub fn has_input(&self) -> bool {
+        match self {
+            Expr::Const(_) => false,
+            Expr::Input(_) => true,
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => a.has_input() || b.has_input(),
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => a.has_input(),
+        }
+    }
+
+    /// Algebraically equivalent form, same for trees which differ only in
+    /// order of `+` and `*` operands or in number of constants folded together.
+    ///
+    /// Chains of `+` and `*` are flattened and sorted with a single constant first,
+    /// `a - c` and `a / c` become `a + c` and `a * c`, operations on constants
+    /// alone become a constant, and constants are numbered left to right.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rustamath_physics::Expr;
+    /// let expr: Expr = "x0*c0*(x1*c1)".parse().unwrap();
+    /// assert_eq!(expr.canonical().to_string(), "c0*x0*x1");
+    /// let expr: Expr = "x0 - c0 + sin(c1 - c2)".parse().unwrap();
+    /// assert_eq!(expr.canonical().to_string(), "c0 + x0");
+    /// ```
+    pub fn canonical(&self) -> Expr {
+        let mut e = self.canonical_shape();
+        e.renumber_constants(&mut 0);
+        e
+    }
+
+    /// Canonical form with all constants `c0`, so that operands sort by structure only.
+    fn canonical_shape(&self) -> Expr {
+        let unary = |op: UnaryOp, a: &Expr| match a.canonical_shape() {
+            Expr::Const(_) => Expr::Const(0),
+            a => op(Box::new(a)),
+        };
+        match self {
+            Expr::Const(_) => Expr::Const(0),
+            Expr::Input(_) => self.clone(),
+            Expr::Add(a, b) => fold_operands(Expr::Add, a.canonical_shape(), b.canonical_shape()),
+            Expr::Mul(a, b) => fold_operands(Expr::Mul, a.canonical_shape(), b.canonical_shape()),
+            Expr::Sub(a, b) => match (a.canonical_shape(), b.canonical_shape()) {
+                (a, Expr::Const(_)) => fold_operands(Expr::Add, a, Expr::Const(0)),
+                (a, b) => Expr::Sub(Box::new(a), Box::new(b)),
+            },
+            Expr::Div(a, b) => match (a.canonical_shape(), b.canonical_shape()) {
+                (a, Expr::Const(_)) => fold_operands(Expr::Mul, a, Expr::Const(0)),
+                (a, b) => Expr::Div(Box::new(a), Box::new(b)),
+            },
+            Expr::Pow(a, b) => match (a.canonical_shape(), b.canonical_shape()) {
+                (Expr::Const(_), Expr::Const(_)) => Expr::Const(0),
+                (a, b) => Expr::Pow(Box::new(a), Box::new(b)),
+            },
+            Expr::Sin(a) => unary(Expr::Sin, a),
+            Expr::Exp(a) => unary(Expr::Exp, a),
+            Expr::Log(a) => unary(Expr::Log, a),
+            Expr::Sqrt(a) => unary(Expr::Sqrt, a),
+        }
+    }
+
+    /// Values at a few fixed probe points with fixed constants,
+    /// `None` if any value is not finite.
+    fn fingerprint(&self, nr_inputs: usize) -> Option<Vec<u64>> {
+        // Low mantissa bits are dropped to ignore rounding errors
+        const DROP_BITS: u32 = 16;
+        const NR_PROBES: usize = 5;
+        const GOLDEN: f64 = 0.618_033_988_749_895;
+
+        let cns: Vec<f64> = (0..self.nr_constants()).map(|k| 1.0 + 0.1 * (k as f64 + 2.0).sqrt()).collect();
+        (0..NR_PROBES).map(|probe| {
+            let inp: Vec<f64> = (0..nr_inputs)
+                .map(|i| 0.5 + ((probe * nr_inputs + i + 1) as f64 * GOLDEN).fract() * 1.5)
+                .collect();
+            let value = self.eval(&inp, &cns);
+            value.is_finite().then_some(value.to_bits() >> DROP_BITS)
+        }).collect()
+    }
+
+    /// Node at index `index` in pre-order, root is 0 and last is `size() - 1`;
+    /// `None` if index is out of range.
+    pub(crate) fn subtree(&self, index: usize) -> Option<&Expr> {
+        if index == 0 {
+            return Some(self);
+        }
+        let mut index = index - 1;
+        for child in self.children() {
+            let size = child.size();
+            if index < size {
+                return child.subtree(index);
+            }
+            index -= size;
+        }
+        None
+    }
+
+    /// Mutable node at index `index` in pre-order, see [Expr::subtree].
+    pub(crate) fn subtree_mut(&mut self, index: usize) -> Option<&mut Expr> {
+        if index == 0 {
+            return Some(self);
+        }
+        let mut index = index - 1;
+        for child in self.children_mut() {
+            let size = child.size();
+            if index < size {
+                return child.subtree_mut(index);
+            }
+            index -= size;
+        }
+        None
+    }
+
+    fn children(&self) -> Vec<&Expr> {
+        match self {
+            Expr::Const(_) | Expr::Input(_) => vec![],
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => vec![a, b],
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => vec![a],
+        }
+    }
+
+    fn children_mut(&mut self) -> Vec<&mut Expr> {
+        match self {
+            Expr::Const(_) | Expr::Input(_) => vec![],
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => vec![a, b],
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => vec![a],
+        }
+    }
+
+    /// Number constants left to right as `c0, c1, ...`.
+    pub(crate) fn renumber_constants(&mut self, next: &mut usize) {
+        match self {
+            Expr::Const(i) => { *i = *next; *next += 1; },
+            Expr::Input(_) => (),
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) |
+            Expr::Div(a, b) | Expr::Pow(a, b) => {
+                a.renumber_constants(next);
+                b.renumber_constants(next);
+            },
+            Expr::Sin(a) | Expr::Exp(a) | Expr::Log(a) | Expr::Sqrt(a) => a.renumber_constants(next),
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::Add(..) | Expr::Sub(..) => 1,
+            Expr::Mul(..) | Expr::Div(..) => 2,
+            _ => 3,
+        }
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let operand = |f: &mut fmt::Formatter, e: &Expr, min_precedence: u8| {
+            if e.precedence() < min_precedence { write!(f, "({})", e) } else { write!(f, "{}", e) }
+        };
+        let binary = |f: &mut fmt::Formatter, a: &Expr, op: &str, b: &Expr| {
+            let p = self.precedence();
+            operand(f, a, p)?;
+            write!(f, "{}", op)?;
+            operand(f, b, p + 1)
+        };
+        match self {
+            Expr::Const(i) => write!(f, "c{}", i),
+            Expr::Input(i) => write!(f, "x{}", i),
+            Expr::Add(a, b) => binary(f, a, " + ", b),
+            Expr::Sub(a, b) => binary(f, a, " - ", b),
+            Expr::Mul(a, b) => binary(f, a, "*", b),
+            Expr::Div(a, b) => binary(f, a, "/", b),
+            Expr::Pow(a, b) => write!(f, "pow({}, {})", a, b),
+            Expr::Sin(a) => write!(f, "sin({})", a),
+            Expr::Exp(a) => write!(f, "exp({})", a),
+            Expr::Log(a) => write!(f, "log({})", a),
+            Expr::Sqrt(a) => write!(f, "sqrt({})", a),
+        }
+    }
+}
+
+/// Parse expression written as [Expr] is displayed, e.g. `c0*sin(c1*x0 + c2) + c3`.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{Error, Expr};
+/// let expr: Expr = "c0 + c1*pow(x0, c2)".parse().unwrap();
+/// assert_eq!(expr.eval(&[3.0], &[1.0, 2.0, 2.0]), 19.0);
+/// assert_eq!(expr.to_string(), "c0 + c1*pow(x0, c2)");
+/// assert_eq!("c0 + ".parse::<Expr>(), Err(Error::Syntax(5)));
+/// ```
+impl FromStr for Expr {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Expr> {
+        let mut parser = Parser { text: text.as_bytes(), pos: 0 };
+        let expr = parser.sum()?;
+        match parser.peek() {
+            None => Ok(expr),
+            Some(_) => Err(Error::Syntax(parser.pos)),
+        }
+    }
+}
+
+/// Recursive descent parser of expression text.
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    /// Next character after spaces.
+    fn peek(&mut self) -> Option<u8> {
+        while self.text.get(self.pos) == Some(&b' ') {
+            self.pos += 1;
+        }
+        self.text.get(self.pos).copied()
+    }
+
+    fn expect(&mut self, c: u8) -> Result<()> {
+        if self.peek() != Some(c) {
+            return Err(Error::Syntax(self.pos));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    /// `product (('+' | '-') product)*`
+    fn sum(&mut self) -> Result<Expr> {
+        let mut expr = self.product()?;
+        loop {
+            let op: fn(Box<Expr>, Box<Expr>) -> Expr = match self.peek() {
+                Some(b'+') => Expr::Add,
+                Some(b'-') => Expr::Sub,
+                _ => return Ok(expr),
+            };
+            self.pos += 1;
+            expr = op(Box::new(expr), Box::new(self.product()?));
+        }
+    }
+
+    /// `factor (('*' | '/') factor)*`
+    fn product(&mut self) -> Result<Expr> {
+        let mut expr = self.factor()?;
+        loop {
+            let op: fn(Box<Expr>, Box<Expr>) -> Expr = match self.peek() {
+                Some(b'*') => Expr::Mul,
+                Some(b'/') => Expr::Div,
+                _ => return Ok(expr),
+            };
+            self.pos += 1;
+            expr = op(Box::new(expr), Box::new(self.factor()?));
+        }
+    }
+
+    /// `'(' sum ')' | cN | xN | function '(' sum [',' sum] ')'`
+    fn factor(&mut self) -> Result<Expr> {
+        if self.peek() == Some(b'(') {
+            self.pos += 1;
+            let expr = self.sum()?;
+            self.expect(b')')?;
+            return Ok(expr);
+        }
+
+        let start = self.pos;
+        let name_len = self.text[start..].iter().take_while(|c| c.is_ascii_lowercase()).count();
+        let digits_len = self.text[start + name_len..].iter().take_while(|c| c.is_ascii_digit()).count();
+        let name = &self.text[start..start + name_len];
+        let index: Option<usize> = std::str::from_utf8(&self.text[start + name_len..start + name_len + digits_len])
+            .ok().and_then(|digits| digits.parse().ok());
+        self.pos = start + name_len + digits_len;
+
+        let unary: Option<fn(Box<Expr>) -> Expr> = match name {
+            b"sin" => Some(Expr::Sin),
+            b"exp" => Some(Expr::Exp),
+            b"log" => Some(Expr::Log),
+            b"sqrt" => Some(Expr::Sqrt),
+            _ => None,
+        };
+
+        match (name, index, unary) {
+            (b"c", Some(i), _) => Ok(Expr::Const(i)),
+            (b"x", Some(i), _) => Ok(Expr::Input(i)),
+            (_, None, Some(op)) => {
+                self.expect(b'(')?;
+                let a = self.sum()?;
+                self.expect(b')')?;
+                Ok(op(Box::new(a)))
+            },
+            (b"pow", None, _) => {
+                self.expect(b'(')?;
+                let a = self.sum()?;
+                self.expect(b',')?;
+                let b = self.sum()?;
+                self.expect(b')')?;
+                Ok(Expr::Pow(Box::new(a), Box::new(b)))
+            },
+            _ => Err(Error::Syntax(start)),
+        }
+    }
+}
+
+/// Expression with constants set, runs as any other equation.
+pub struct ExprEquation {
+    expr: Rc<Expr>,
+    cns: Vec<f64>,
+    nr_inp: usize,
+}
+
+impl Equation for ExprEquation {
+    fn run(&mut self, inp: &[f64]) -> Vec<f64> {
+        vec![self.expr.eval(inp, &self.cns)]
+    }
+
+    fn nr_inputs(&self) -> usize {
+        self.nr_inp
+    }
+
+    fn run_into(&mut self, inp: &[f64], out: &mut [f64]) {
+        out[0] = self.expr.eval(inp, &self.cns);
+    }
+
+    fn constant_derivatives(&self, inp: &[f64]) -> Option<Vec<f64>> {
+        Some((0..self.cns.len()).map(|k| self.expr.derivative(inp, &self.cns, k)).collect())
+    }
+
+    fn run_batch(&mut self, inputs: &[f64], outputs: &mut [f64]) {
+        let nr_inp = self.nr_inp.max(1);
+        for (inp, out) in inputs.chunks(nr_inp).zip(outputs.iter_mut()) {
+            *out = self.expr.eval(inp, &self.cns);
+        }
+    }
+}
+
+/// Expression as a model which constants are fitted.
+pub(crate) struct ExprModel {
+    pub expr: Rc<Expr>,
+    pub nr_inp: usize,
+}
+
+impl Model for ExprModel {
+    fn dims(&self) -> (usize, usize, usize) {
+        (1, self.expr.nr_constants(), self.nr_inp)
+    }
+
+    fn make(&self, cns: &[f64]) -> Box<dyn Equation> {
+        Box::new(ExprEquation { expr: self.expr.clone(), cns: cns.to_vec(), nr_inp: self.nr_inp })
+    }
+
+    fn complexity(&self) -> usize {
+        self.expr.size()
+    }
+}
+
+/// Units of expression leaves and output, used to build dimensionally consistent trees.
+pub struct UnitGrammar<'a> {
+    /// Units of input parameters
+    pub inputs: &'a [MksUnit],
+    /// Units constants are allowed to have
+    pub constants: &'a [MksUnit],
+    /// Unit of expression output
+    pub output: MksUnit,
+}
+
+/// Enumerate all expressions with up to `max_size` nodes.
+///
+/// Obviously redundant trees are skipped:
+/// - subtrees without inputs collapse into a single constant;
+/// - operands of `+` and `*` are ordered;
+/// - `a - c` is the same as `a + c` with another constant, and so is `a / c`
+///   as `a * c` when constants may have the inverse unit of `c`;
+/// - `a + a`, `a - a`, `a / a`, `exp(log(a))` and `log(exp(a))`.
+///
+/// Returned expressions depend on at least one input and have constants numbered.
+pub fn enumerate(nr_inputs: usize, max_size: usize) -> Vec<Expr> {
+    let leaves: Vec<(Expr, MksUnit)> = std::iter::once((Expr::Const(0), SCALAR_UNIT))
+        .chain((0..nr_inputs).map(|i| (Expr::Input(i), SCALAR_UNIT)))
+        .collect();
+
+    enumerate_leaves(leaves, &[SCALAR_UNIT], max_size, None)
+}
+
+/// Enumerate dimensionally consistent expressions with up to `max_size` nodes.
+///
+/// Unit of every subexpression is tracked, and a tree is built only if:
+/// - `+` and `-` operands have same unit;
+/// - `sin`, `exp`, `log` and `pow` arguments are unit-less;
+/// - `sqrt` argument unit has even powers;
+/// - unit of the whole expression is `grammar.output`.
+///
+/// Same pruning of redundant trees as in [enumerate] is done.
+pub fn enumerate_with_units(grammar: &UnitGrammar, max_size: usize) -> Vec<Expr> {
+    let leaves: Vec<(Expr, MksUnit)> = grammar.constants.iter().map(|u| (Expr::Const(0), *u))
+        .chain(grammar.inputs.iter().enumerate().map(|(i, u)| (Expr::Input(i), *u)))
+        .collect();
+
+    let mut exprs = enumerate_leaves(leaves, grammar.constants, max_size, Some(grammar.output));
+
+    // Same tree may be built with constants of different units.
+    let mut seen: HashSet<Expr> = HashSet::new();
+    exprs.retain(|e| seen.insert(e.clone()));
+    exprs
+}
+
+/// Enumerate dimensionally consistent expressions up to `max_depth` levels
+/// and `max_size` nodes, ordered by increasing size.
+///
+/// Same unit rules and pruning of redundant trees as in [enumerate_with_units],
+/// use [dedupe] to also drop algebraically equivalent trees.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::expr::{enumerate_to_depth, UnitGrammar};
+/// use rustamath_mks::*;
+/// let grammar = UnitGrammar { inputs: &[TIME_UNIT], constants: &[VELOCITY_UNIT], output: DISTANCE_UNIT };
+/// let exprs = enumerate_to_depth(&grammar, 3, 7);
+/// assert_eq!(exprs[0].to_string(), "c0*x0");
+/// assert!(exprs.iter().all(|e| e.depth() <= 3));
+/// ```
+pub fn enumerate_to_depth(grammar: &UnitGrammar, max_depth: usize, max_size: usize) -> Vec<Expr> {
+    let leaves: Vec<(Expr, MksUnit)> = grammar.constants.iter().map(|u| (Expr::Const(0), *u))
+        .chain(grammar.inputs.iter().enumerate().map(|(i, u)| (Expr::Input(i), *u)))
+        .collect();
+
+    if max_depth == 0 || max_size == 0 {
+        return Vec::new();
+    }
+
+    // levels[depth] holds all trees of that depth with their units
+    let mut levels: Vec<Vec<(Expr, MksUnit)>> = vec![Vec::new(); max_depth + 1];
+    levels[1] = leaves;
+
+    for depth in 2..=max_depth {
+        let mut level: Vec<(Expr, MksUnit)> = Vec::new();
+
+        for (a, ua) in levels[depth - 1].iter() {
+            grow_unary(a, *ua, &mut level);
+        }
+
+        for depth_a in 1..depth {
+            for depth_b in 1..depth {
+                if depth_a.max(depth_b) != depth - 1 {
+                    continue;
+                }
+                for (index_a, (a, ua)) in levels[depth_a].iter().enumerate() {
+                    for (index_b, (b, ub)) in levels[depth_b].iter().enumerate() {
+                        if a.size() + b.size() < max_size {
+                            let ordered = (depth_a, index_a) <= (depth_b, index_b);
+                            grow_binary((a, *ua), (b, *ub), ordered, grammar.constants, &mut level);
+                        }
+                    }
+                }
+            }
+        }
+
+        level.retain(|(e, _)| e.size() <= max_size);
+        levels[depth] = level;
+    }
+
+    let mut exprs: Vec<Expr> = levels.into_iter().flatten()
+        .filter(|(e, unit)| e.has_input() && *unit == grammar.output)
+        .map(|(e, _)| e)
+        .collect();
+    for e in exprs.iter_mut() {
+        e.renumber_constants(&mut 0);
+    }
+    exprs.sort_by_key(|e| e.size());
+
+    let mut seen: HashSet<Expr> = HashSet::new();
+    exprs.retain(|e| seen.insert(e.clone()));
+    exprs
+}
+
+/// Drop algebraically equivalent expressions, keep the first one in canonical form.
+///
+/// Expressions are equivalent if they have same [Expr::canonical] form,
+/// or same values at a few probe points of `nr_inputs` inputs with same constants.
+/// Order of kept expressions is preserved, so pass them simplest first.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{expr::dedupe, Expr};
+/// let exprs: Vec<Expr> = ["c0*x0", "x0*c0*c1", "c0*x0*x0/x0", "c0*x0 + c1"].iter().map(|s| s.parse().unwrap()).collect();
+/// let unique = dedupe(exprs, 1);
+/// assert_eq!(unique.len(), 2);
+/// assert_eq!(unique[1].to_string(), "c0 + c1*x0");
+/// ```
+pub fn dedupe(exprs: Vec<Expr>, nr_inputs: usize) -> Vec<Expr> {
+    let mut forms: HashSet<Expr> = HashSet::new();
+    let mut fingerprints: HashSet<Vec<u64>> = HashSet::new();
+
+    exprs.into_iter()
+        .map(|e| e.canonical())
+        .filter(|e| forms.insert(e.clone()))
+        .filter(|e| e.fingerprint(nr_inputs).is_none_or(|fingerprint| fingerprints.insert(fingerprint)))
+        .collect()
+}
+
+/// Flatten chain of `op` operands, fold constants into one first operand and sort the rest.
+fn fold_operands(op: BinaryOp, a: Expr, b: Expr) -> Expr {
+    let kind = std::mem::discriminant(&op(Box::new(Expr::Const(0)), Box::new(Expr::Const(0))));
+
+    let mut stack = vec![b, a];
+    let mut operands: Vec<Expr> = Vec::new();
+    let mut has_const = false;
+    while let Some(e) = stack.pop() {
+        match e {
+            Expr::Add(x, y) | Expr::Mul(x, y) if std::mem::discriminant(&e) == kind => {
+                stack.push(*y);
+                stack.push(*x);
+            },
+            Expr::Const(_) => has_const = true,
+            e => operands.push(e),
+        }
+    }
+
+    operands.sort_by_cached_key(|e| e.to_string());
+    if has_const || operands.is_empty() {
+        operands.insert(0, Expr::Const(0));
+    }
+    operands.into_iter()
+        .reduce(|acc, e| op(Box::new(acc), Box::new(e)))
+        .unwrap_or(Expr::Const(0))
+}
+
+/// Constructor of unary operation node
+type UnaryOp = fn(Box<Expr>) -> Expr;
+/// Constructor of binary operation node
+type BinaryOp = fn(Box<Expr>, Box<Expr>) -> Expr;
+
+/// Build trees from leaves, if `output` unit is set then only trees with that unit are returned.
+///
+/// Units of all leaves are `SCALAR_UNIT` when there are no unit constraints,
+/// `constants` are units constants may have.
+fn enumerate_leaves(leaves: Vec<(Expr, MksUnit)>, constants: &[MksUnit], max_size: usize, output: Option<MksUnit>) -> Vec<Expr> {
+    // levels[size] holds all trees of that size with their units
+    let mut levels: Vec<Vec<(Expr, MksUnit)>> = vec![Vec::new(); max_size + 1];
+
+    if max_size == 0 {
+        return Vec::new();
+    }
+
+    levels[1] = leaves;
+
+    for size in 2..=max_size {
+        let mut level: Vec<(Expr, MksUnit)> = Vec::new();
+
+        for (a, ua) in levels[size - 1].iter() {
+            grow_unary(a, *ua, &mut level);
+        }
+
+        for size_a in 1..size - 1 {
+            let size_b = size - 1 - size_a;
+            for (index_a, (a, ua)) in levels[size_a].iter().enumerate() {
+                for (index_b, (b, ub)) in levels[size_b].iter().enumerate() {
+                    let ordered = (size_a, index_a) <= (size_b, index_b);
+                    grow_binary((a, *ua), (b, *ub), ordered, constants, &mut level);
+                }
+            }
+        }
+
+        levels[size] = level;
+    }
+
+    let mut exprs: Vec<Expr> = levels.into_iter().flatten()
+        .filter(|(e, unit)| e.has_input() && output.is_none_or(|out| *unit == out))
+        .map(|(e, _)| e)
+        .collect();
+    for e in exprs.iter_mut() {
+        e.renumber_constants(&mut 0);
+    }
+    exprs
+}
+
+/// Push unary operations on `a` which are consistent with unit `ua`.
+fn grow_unary(a: &Expr, ua: MksUnit, level: &mut Vec<(Expr, MksUnit)>) {
+    if !a.has_input() {
+        return;
+    }
+    let unary: [UnaryOp; 4] = [Expr::Sin, Expr::Exp, Expr::Log, Expr::Sqrt];
+    for op in unary {
+        let e = op(Box::new(a.clone()));
+        let unit = match &e {
+            Expr::Exp(a) if matches!(**a, Expr::Log(_)) => None,
+            Expr::Log(a) if matches!(**a, Expr::Exp(_)) => None,
+            Expr::Sqrt(_) => unit_sqrt(ua),
+            _ => if ua == SCALAR_UNIT { Some(SCALAR_UNIT) } else { None },
+        };
+        if let Some(unit) = unit {
+            level.push((e, unit));
+        }
+    }
+}
+
+/// Push binary operations on `a` and `b` which are consistent with their units,
+/// `+` and `*` only if operands are `ordered`.
+///
+/// `a - c` is dropped for constant `c`, `a + c` fits the same; `a / c` is dropped only
+/// if `constants` has the inverse unit of `c`, so that `a * c` fits the same.
+fn grow_binary(
+    a: (&Expr, MksUnit),
+    b: (&Expr, MksUnit),
+    ordered: bool,
+    constants: &[MksUnit],
+    level: &mut Vec<(Expr, MksUnit)>
+) {
+    let ((a, ua), (b, ub)) = (a, b);
+    if !a.has_input() && !b.has_input() {
+        return;
+    }
+    let same = a == b;
+    let b_is_const = matches!(b, Expr::Const(_));
+    let like_units = if ua == ub { Some(ua) } else { None };
+    let scalars = if ua == SCALAR_UNIT && ub == SCALAR_UNIT { Some(SCALAR_UNIT) } else { None };
+
+    let mut ops: Vec<(BinaryOp, Option<MksUnit>)> = Vec::with_capacity(5);
+    if ordered && !same {
+        ops.push((Expr::Add, like_units));
+    }
+    if ordered {
+        ops.push((Expr::Mul, Some(ua * ub)));
+    }
+    if !same && !b_is_const {
+        ops.push((Expr::Sub, like_units));
+    }
+    let inverse_const = b_is_const && constants.contains(&(SCALAR_UNIT / ub));
+    if !same && !inverse_const {
+        ops.push((Expr::Div, Some(ua / ub)));
+    }
+    ops.push((Expr::Pow, scalars));
+
+    for (op, unit) in ops {
+        if let Some(unit) = unit {
+            level.push((op(Box::new(a.clone()), Box::new(b.clone())), unit));
+        }
+    }
+}
@@ -3,57 +3,434 @@
 //! (c) 2023 Igor Lesik
 //! MIT license
 //!
-//use super::super::{Equation};
-use super::super::equations::{BuildTuple};
+use std::cell::RefCell;
+use super::super::derivative::central_difference;
+use super::{sigma, Model};
+use super::linalg;
+use super::loss::Loss;
+use super::pool::PoolOptions;
+use super::rng::{latin_hypercube, Rng};
+use super::units;
+
+/// Default search box of a constant is `±BOUND_FACTOR` times its typical magnitude.
+const BOUND_FACTOR: f64 = 10.0;
+
+/// Maximum number of reweighting passes with robust loss.
+const MAX_REWEIGHTINGS: usize = 20;
+
+/// Minimization method used to fit constants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FitMethod {
+    /// Nelder-Mead downhill simplex
+    Simplex,
+    /// Levenberg-Marquardt non-linear least squares,
+    /// uses analytic Jacobian when model provides it, numerical otherwise
+    LevenbergMarquardt,
+}
+
+/// Options of fitting constants.
+///
+/// With `starts > 1` the local minimization is repeated from several starting
+/// points and the best fit is kept: the initial guess, constants' typical
+/// magnitudes estimated from their units and measured data, and Latin
+/// hypercube samples inside `bounds`. When `bounds` is `None` each constant is
+/// searched within `±10` times its typical magnitude.
+/// `initial` and `bounds` are ignored when their length does not match
+/// the number of fitted constants.
+///
+/// Robust `loss` is minimized by iteratively reweighted least squares,
+/// see [Loss].
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{FitMethod, FitOptions};
+/// let options = FitOptions { method: FitMethod::LevenbergMarquardt, max_iterations: 500, ..FitOptions::default() };
+/// assert_eq!(options.tolerance, 1.0e-3);
+///
+/// let options = FitOptions { starts: 16, bounds: Some(vec![(0.0, 20.0), (-5.0, 5.0)]), ..FitOptions::default() };
+/// assert_eq!(options.initial, None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FitOptions {
+    /// Minimization method
+    pub method: FitMethod,
+    /// Initial simplex displacement along each constant
+    pub step: f64,
+    /// Fractional tolerance of χ² to stop iterations
+    pub tolerance: f64,
+    /// Fractional tolerance of constant in one-dimensional search
+    pub param_tolerance: f64,
+    /// Maximum number of iterations
+    pub max_iterations: usize,
+    /// Number of starting points of the local minimization
+    pub starts: usize,
+    /// Seed of random starting points
+    pub seed: u64,
+    /// Lower and upper bound of starting points of every constant
+    pub bounds: Option<Vec<(f64, f64)>>,
+    /// Initial guess of constants, all ones when `None`
+    pub initial: Option<Vec<f64>>,
+    /// Loss of normalized residuals
+    pub loss: Loss,
+    /// Worker pool running candidate equations in parallel
+    pub pool: PoolOptions,
+}
+
+impl Default for FitOptions {
+    fn default() -> Self {
+        FitOptions {
+            method: FitMethod::Simplex,
+            step: 0.1,
+            tolerance: 1.0e-3,
+            param_tolerance: 1.0e-6,
+            max_iterations: 150,
+            starts: 1,
+            seed: 0,
+            bounds: None,
+            initial: None,
+            loss: Loss::Chi2,
+            pool: PoolOptions::default(),
+        }
+    }
+}
 
 /// Fitting entry function
+///
+/// Minimizes χ² = ∑ ((Oᵢ - fᵢ)/sigmaᵢ)², see `goodness_of_fit` about `ssigmas`.
+#[allow(clippy::too_many_arguments)]
 pub fn fit(
-    builder: &BuildTuple,
+    model: &dyn Model,
     inputs: &[f64],
     outputs: &[f64],
+    ssigmas: &[f64],
     params: &mut [f64],
     nr_measurements: usize,
-    nr_inp_params: usize
+    nr_inp_params: usize,
+    options: &FitOptions
 )
 {
-    if params.len() == 1 {
-        panic!();
+    let (nr_out_params, _, _) = model.dims();
+    let weights: Vec<f64> = (0..outputs.len()).map(|k| 1.0 / sigma(ssigmas, nr_out_params, k)).collect();
+
+    let data = Data { inputs, outputs, weights: &weights, nr_measurements, nr_inp_params };
+
+    let initial = options.initial.as_ref().filter(|initial| initial.len() == params.len());
+    if let Some(initial) = initial {
+        params.copy_from_slice(initial);
+    }
+
+    fit_global(model, &data, params, options, initial.is_some());
+
+    if options.loss == Loss::Chi2 {
+        return;
+    }
+
+    // Iteratively reweighted least squares, large residuals get smaller weights.
+    let mut robust_weights = weights.clone();
+    for _ in 0..MAX_REWEIGHTINGS {
+        let previous = params.to_vec();
+        robust_weights.copy_from_slice(&weights);
+        reweight(&mut robust_weights, model, &data, params, options.loss);
+        let robust_data = Data { weights: &robust_weights, ..data };
+        if !(params.len() == 1 && fit_linear_one_dimension(model, &robust_data, params)) {
+            fit_local(model, &robust_data, params, options);
+        }
+        let converged = params.iter().zip(previous.iter())
+            .all(|(p, q)| (p - q).abs() <= options.param_tolerance * p.abs().max(1.0));
+        if converged {
+            break;
+        }
+    }
+}
+
+/// Multiply weights by robust loss weights of current residuals.
+fn reweight(weights: &mut [f64], model: &dyn Model, data: &Data, params: &[f64], loss: Loss) {
+    let predictions = data.predict(model, params);
+    for ((w, output), predicted) in weights.iter_mut().zip(data.outputs.iter()).zip(predictions.iter()) {
+        let r = (output - predicted) * *w;
+        let robust = loss.weight(r);
+        if robust.is_finite() {
+            *w *= robust.sqrt();
+        }
+    }
+}
+
+/// Minimize χ² from initial constants, or from several starting points.
+fn fit_global(model: &dyn Model, data: &Data, params: &mut [f64], options: &FitOptions, has_initial: bool)
+{
+    let (inputs, outputs) = (data.inputs, data.outputs);
+
+    if params.len() == 1 && fit_linear_one_dimension(model, data, params) {
+        return;
+    }
+
+    if options.starts <= 1 {
+        fit_local(model, data, params, options);
+        return;
+    }
+
+    let scales = match model.units() {
+        Some(units) => units::constant_scales(units, inputs, outputs),
+        None => vec![1.0; params.len()],
+    };
+
+    let bounds: Vec<(f64, f64)> = match options.bounds.as_ref().filter(|bounds| bounds.len() == params.len()) {
+        Some(bounds) => bounds.clone(),
+        None => scales.iter().map(|scale| (-BOUND_FACTOR * scale, BOUND_FACTOR * scale)).collect(),
+    };
+
+    let mut starts: Vec<Vec<f64>> = vec![params.to_vec()];
+    if !has_initial {
+        starts.push(scales);
     }
-    else {
-        fit_multidimensions(builder, inputs, outputs, params, nr_measurements, nr_inp_params);
+    let mut rng = Rng::new(options.seed);
+    let nr_random_starts = options.starts.saturating_sub(starts.len());
+    starts.append(&mut latin_hypercube(&mut rng, &bounds, nr_random_starts));
+    starts.truncate(options.starts);
+
+    let mut best_chi2 = f64::NAN;
+    for mut start in starts {
+        fit_local(model, data, &mut start, options);
+        let chi2 = data.chi2(&data.predict(model, &start));
+        if chi2 < best_chi2 || (best_chi2.is_nan() && !chi2.is_nan()) {
+            best_chi2 = chi2;
+            params.copy_from_slice(&start);
+        }
     }
 }
 
-fn fit_multidimensions(
-    builder: &BuildTuple,
+/// Local minimization from current constants.
+fn fit_local(model: &dyn Model, data: &Data, params: &mut [f64], options: &FitOptions)
+{
+    match options.method {
+        FitMethod::LevenbergMarquardt => fit_levenberg_marquardt(model, data, params, options),
+        FitMethod::Simplex if params.len() == 1 => fit_one_dimension(model, data, params, options),
+        FitMethod::Simplex => fit_multidimensions(model, data, params, options),
+    }
+}
+
+/// Measurements to fit.
+struct Data<'a> {
+    inputs: &'a [f64],
+    outputs: &'a [f64],
+    /// Weight `1/sigma` of every output value
+    weights: &'a [f64],
+    nr_measurements: usize,
+    nr_inp_params: usize,
+}
+
+impl Data<'_> {
+    /// Predicted output values for all measurements.
+    fn predict(&self, model: &dyn Model, params: &[f64]) -> Vec<f64> {
+        predict(model, self.inputs, params, self.nr_measurements, self.nr_inp_params)
+    }
+
+    /// χ² of predicted values.
+    fn chi2(&self, predictions: &[f64]) -> f64 {
+        self.outputs.iter().zip(predictions.iter()).zip(self.weights.iter())
+            .map(|((output, predicted), w)| (output - predicted) * (output - predicted) * w * w)
+            .sum()
+    }
+}
+
+/// Predicted output values for all measurements.
+pub(crate) fn predict(
+    model: &dyn Model,
     inputs: &[f64],
-    outputs: &[f64],
-    params: &mut [f64],
+    params: &[f64],
     nr_measurements: usize,
     nr_inp_params: usize
-)
+) -> Vec<f64>
 {
-    use rustamath_mnmz::amoeba;
+    let (nr_out_params, _, _) = model.dims();
+    let mut predictions: Vec<f64> = vec![0.0; nr_measurements * nr_out_params];
+    predict_into(model, &inputs[..nr_measurements * nr_inp_params], params, &mut predictions);
+    predictions
+}
 
-    let fun_chi2 = |params_to_fit: &[f64]| {
-        let mut chi2: f64 = 0.0_f64;
-        #[allow(clippy::needless_range_loop)]
-        for i in 0..nr_measurements {
-            let input_start_index = i * nr_inp_params;
-            let input_end_index = input_start_index + nr_inp_params;
+/// Write predicted output values for all measurements to `predictions`.
+///
+/// Equation is made once and evaluated in one batch.
+pub(crate) fn predict_into(model: &dyn Model, inputs: &[f64], params: &[f64], predictions: &mut [f64])
+{
+    let mut equation = model.make(params);
+    equation.run_batch(inputs, predictions);
+}
+
+/// Fit single constant when model is linear in its parameter, `f(c) = f(0) + c*(f(1) - f(0))`.
+///
+/// Exact linear least-squares solution is used, return `false` if model is not linear.
+fn fit_linear_one_dimension(model: &dyn Model, data: &Data, params: &mut [f64]) -> bool
+{
+    let f0 = data.predict(model, &[0.0]);
+    let f1 = data.predict(model, &[1.0]);
+    let f2 = data.predict(model, &[2.0]);
 
-            let mut equation = (builder.new)(params_to_fit);
-            let prediction = equation.run(&inputs[input_start_index..input_end_index]);
+    let is_linear = f0.iter().zip(f1.iter()).zip(f2.iter()).all(|((f0, f1), f2)| {
+        let second_diff = f2 - 2.0 * f1 + f0;
+        second_diff.abs() <= 1.0e-9 * (f0.abs() + f1.abs() + f2.abs() + 1.0)
+    });
 
-            let diff = outputs[i] - prediction[0];
-            chi2 += diff * diff;
+    if is_linear {
+        let (mut num, mut den) = (0.0_f64, 0.0_f64);
+        for (((output, f0), f1), w) in data.outputs.iter().zip(f0.iter()).zip(f1.iter()).zip(data.weights.iter()) {
+            let slope = f1 - f0;
+            num += (output - f0) * slope * w * w;
+            den += slope * slope * w * w;
         }
+        if den > 0.0 && (num / den).is_finite() {
+            params[0] = num / den;
+            return true;
+        }
+    }
+
+    false
+}
+
+/// Fit single constant with bracketed Brent's search.
+fn fit_one_dimension(model: &dyn Model, data: &Data, params: &mut [f64], options: &FitOptions)
+{
+    use rustamath_mnmz::brent_search;
+
+    let fun_chi2 = |param: f64| {
+        let chi2 = data.chi2(&data.predict(model, &[param]));
+        // Keep bracketing away from undefined regions.
+        if chi2.is_nan() { f64::MAX } else { chi2 }
+    };
+
+    let (min, _fmin, _nriter) = brent_search(fun_chi2, params[0], params[0] + options.step,
+        options.param_tolerance, options.max_iterations);
+    if min.is_finite() {
+        params[0] = min;
+    }
+}
+
+fn fit_multidimensions(model: &dyn Model, data: &Data, params: &mut [f64], options: &FitOptions)
+{
+    use rustamath_mnmz::amoeba;
+
+    let (inputs, outputs, weights) = (data.inputs, data.outputs, data.weights);
+    let inputs = &inputs[..data.nr_measurements * data.nr_inp_params];
+
+    let predictions = RefCell::new(vec![0.0_f64; outputs.len()]);
+
+    let fun_chi2 = |params_to_fit: &[f64]| {
+        let mut predictions = predictions.borrow_mut();
+        predict_into(model, inputs, params_to_fit, &mut predictions);
+
+        let chi2: f64 = outputs.iter().zip(predictions.iter()).zip(weights.iter())
+            .map(|((output, predicted), w)| (output - predicted) * w)
+            .map(|diff| diff * diff)
+            .sum();
         //dbg!(chi2, params_to_fit);
         chi2
     };
 
-    let (min, _fmin, _nriter) = amoeba(fun_chi2, params, 0.1, 1.0e-3, 150);
+    let (min, _fmin, _nriter) = amoeba(fun_chi2, params, options.step, options.tolerance, options.max_iterations);
     params.copy_from_slice(&min);
 
     //dbg!((min, _fmin, _nriter));
-}
\ No newline at end of file
+}
+
+/// Jacobian of all predicted values with respect to constants, row-major `nr_values x nr_params`.
+///
+/// Analytic if equation of the model provides it, otherwise central finite differences.
+pub(crate) fn jacobian(
+    model: &dyn Model,
+    inputs: &[f64],
+    params: &[f64],
+    nr_measurements: usize,
+    nr_inp_params: usize
+) -> Vec<f64>
+{
+    let mut jac: Vec<f64> = Vec::new();
+
+    // Equation is built once for all measurements.
+    let equation = model.make(params);
+    let analytic = (0..nr_measurements).try_for_each(|i| {
+        let input_start_index = i * nr_inp_params;
+        let input_end_index = input_start_index + nr_inp_params;
+        let mut rows = equation.constant_derivatives(&inputs[input_start_index..input_end_index])?;
+        jac.append(&mut rows);
+        Some(())
+    });
+
+    if analytic.is_some() {
+        return jac;
+    }
+
+    central_difference(params, |p| predict(model, inputs, p, nr_measurements, nr_inp_params))
+}
+
+/// Levenberg-Marquardt non-linear least squares.
+///
+/// References:
+///
+/// 1. William H. Press - Numerical recipes, the art of scientific computing.
+///    Cambridge University Press (2007).
+fn fit_levenberg_marquardt(model: &dyn Model, data: &Data, params: &mut [f64], options: &FitOptions)
+{
+    let nr_params = params.len();
+
+    let fun_chi2 = |params_to_fit: &[f64]| {
+        let predictions = data.predict(model, params_to_fit);
+        (data.chi2(&predictions), predictions)
+    };
+
+    let (mut chi2, mut predictions) = fun_chi2(params);
+    if !chi2.is_finite() {
+        return;
+    }
+
+    let mut lambda = 1.0e-3_f64;
+    let mut alpha = vec![0.0_f64; nr_params * nr_params];
+    let mut beta = vec![0.0_f64; nr_params];
+
+    for _ in 0..options.max_iterations {
+        let jac = jacobian(model, data.inputs, params, data.nr_measurements, data.nr_inp_params);
+
+        alpha.iter_mut().for_each(|x| *x = 0.0);
+        beta.iter_mut().for_each(|x| *x = 0.0);
+        for (k, (output, predicted)) in data.outputs.iter().zip(predictions.iter()).enumerate() {
+            let row = &jac[k * nr_params..(k + 1) * nr_params];
+            let w2 = data.weights[k] * data.weights[k];
+            let diff = output - predicted;
+            for j in 0..nr_params {
+                beta[j] += row[j] * diff * w2;
+                for l in 0..nr_params {
+                    alpha[j * nr_params + l] += row[j] * row[l] * w2;
+                }
+            }
+        }
+
+        // Try smaller steps along gradient until χ² decreases.
+        let mut improved = false;
+        while lambda < 1.0e10 {
+            let mut a = alpha.clone();
+            for j in 0..nr_params {
+                a[j * nr_params + j] *= 1.0 + lambda;
+                if a[j * nr_params + j] == 0.0 { a[j * nr_params + j] = lambda; }
+            }
+            let mut delta = beta.clone();
+            if linalg::solve(&mut a, &mut delta, nr_params) {
+                let trial: Vec<f64> = params.iter().zip(delta.iter()).map(|(p, d)| p + d).collect();
+                let (trial_chi2, trial_predictions) = fun_chi2(&trial);
+                if trial_chi2 < chi2 {
+                    let converged = chi2 - trial_chi2 <= options.tolerance * trial_chi2 || trial_chi2 < f64::MIN_POSITIVE;
+                    params.copy_from_slice(&trial);
+                    chi2 = trial_chi2;
+                    predictions = trial_predictions;
+                    lambda = (lambda / 10.0).max(1.0e-12);
+                    improved = !converged;
+                    break;
+                }
+            }
+            lambda *= 10.0;
+        }
+
+        if !improved {
+            break;
+        }
+    }
+}
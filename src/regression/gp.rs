@@ -0,0 +1,365 @@
+//! Genetic programming search of expression trees.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - John R. Koza - Genetic programming: on the programming of computers
+//!   by means of natural selection. MIT Press (1992)
+//! - <https://en.wikipedia.org/wiki/Genetic_programming>
+//!
+//! Population of expression trees evolves by tournament selection, subtree crossover
+//! and subtree mutation; constants of every tree are fitted with `regression::fit`
+//! and the tree is scored by the criterion of the regression run.
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::time::Instant;
+use rustamath_mks::*;
+use super::super::{find_equation_by_units, EQUATIONS};
+use super::budget::{self, Budget};
+use super::config::RegressionConfig;
+use super::expr::{Expr, ExprModel};
+use super::rng::{self, Rng};
+use super::units::unit_sqrt;
+use super::{check_data, cmp_nan_last, fit_and_score, FitResult, Result};
+
+/// Random stream of genetic operators, fitted trees use their evaluation order as stream.
+const GP_STREAM: u64 = u64::MAX - 1;
+
+/// Probability that random tree stops growing at a node above maximum depth.
+const LEAF_PROBABILITY: f64 = 0.3;
+
+/// Probability that random leaf is an input rather than a constant.
+const INPUT_PROBABILITY: f64 = 0.6;
+
+/// Attempts per population member to build a valid child before giving up.
+const MAX_ATTEMPTS: usize = 10;
+
+/// Options of genetic programming search.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::GpOptions;
+/// let gp = GpOptions { population: 200, generations: 50, repair_units: false, ..GpOptions::default() };
+/// ```
+#[derive(Debug, Clone)]
+pub struct GpOptions {
+    /// Number of trees in population
+    pub population: usize,
+    /// Number of generations bred after the initial population
+    pub generations: usize,
+    /// Number of trees competing in tournament selection
+    pub tournament: usize,
+    /// Probability that child is made by crossover of two parents
+    pub crossover_rate: f64,
+    /// Probability that child is mutated
+    pub mutation_rate: f64,
+    /// Maximum depth of random trees of initial population and mutations
+    pub max_depth: usize,
+    /// Maximum number of nodes in tree, larger children are rejected
+    pub max_size: usize,
+    /// Number of best trees copied unchanged to next generation
+    pub elitism: usize,
+    /// Make trees dimensionally consistent by multiplying offending subtrees by constants
+    pub repair_units: bool,
+    /// Seed initial population with formulas of `EQUATIONS` entries with matching units
+    pub seed_equations: bool,
+    /// Maximum number of returned best fitting expressions
+    pub max_results: usize,
+}
+
+impl Default for GpOptions {
+    fn default() -> Self {
+        GpOptions {
+            population: 64,
+            generations: 20,
+            tournament: 4,
+            crossover_rate: 0.7,
+            mutation_rate: 0.2,
+            max_depth: 3,
+            max_size: 15,
+            elitism: 2,
+            repair_units: true,
+            seed_equations: true,
+            max_results: 10,
+        }
+    }
+}
+
+/// Evolve population of expression trees, return every fitted tree in order of fitting.
+///
+/// Random choices are drawn from the seed of `config` on the calling thread only,
+/// so same seed gives same trees regardless of worker threads.
+/// Fail on malformed data before any tree is built.
+pub fn evolve(
+    unit_inputs: &[MksUnit],
+    unit_outputs: &[MksUnit],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    gp: &GpOptions,
+    config: &RegressionConfig
+) -> Result<Vec<(Expr, FitResult)>>
+{
+    let nr_inp = unit_inputs.len();
+    let nr_measurements = check_data(nr_inp, unit_outputs.len(), inputs, outputs, ssigmas)?;
+    let mut breeder = Breeder {
+        rng: Rng::new(rng::derive_seed(config.seed, GP_STREAM)),
+        gp,
+        nr_inp,
+        nr_measurements,
+        units: gp.repair_units.then_some((unit_inputs, unit_outputs[0])),
+    };
+
+    let mut population: Vec<Expr> = Vec::with_capacity(gp.population);
+    if gp.seed_equations {
+        population.extend(equation_seeds(unit_inputs, unit_outputs).into_iter().filter_map(|e| breeder.finish(e)));
+    }
+    for _ in 0..gp.population * MAX_ATTEMPTS {
+        if population.len() >= gp.population {
+            break;
+        }
+        let tree = breeder.random_tree(gp.max_depth);
+        population.extend(breeder.finish(tree));
+    }
+
+    let start = Instant::now();
+    let mut fitted: Vec<(Expr, FitResult)> = Vec::new();
+    let mut index: HashMap<Expr, usize> = HashMap::new();
+
+    for generation in 0..=gp.generations {
+        // Fit only trees not seen before, each with its own random stream.
+        let mut seen: HashSet<&Expr> = HashSet::new();
+        let fresh: Vec<(usize, Expr)> = population.iter()
+            .filter(|e| !index.contains_key(*e) && seen.insert(*e))
+            .enumerate()
+            .map(|(i, e)| (fitted.len() + i, e.clone()))
+            .collect();
+
+        let remaining = Budget {
+            time: config.budget.time.map(|time| time.saturating_sub(start.elapsed())),
+            evaluations: config.budget.evaluations.map(|n| n.saturating_sub(fitted.len())),
+            target_reduced_chi2: config.budget.target_reduced_chi2,
+        };
+
+        let fits: Vec<Result<(Expr, FitResult)>> = budget::map(&fresh, &remaining, &config.fit.pool, |(stream, e)| {
+            let model = ExprModel { expr: Rc::new(e.clone()), nr_inp };
+            Ok((e.clone(), fit_and_score(&model, inputs, outputs, ssigmas, &config.fit_options(*stream))?))
+        }, |fit| fit.as_ref().map_or(f64::NAN, |(_, res)| res.reduced_chi2));
+        let fits: Vec<(Expr, FitResult)> = fits.into_iter().collect::<Result<_>>()?;
+
+        let exhausted = fits.len() < fresh.len();
+        let on_target = fits.iter()
+            .any(|(_, res)| config.budget.target_reduced_chi2.is_some_and(|target| res.reduced_chi2 <= target));
+
+        for (e, res) in fits {
+            index.insert(e.clone(), fitted.len());
+            fitted.push((e, res));
+        }
+        population.retain(|e| index.contains_key(e));
+
+        if exhausted || on_target || generation == gp.generations || population.is_empty() {
+            break;
+        }
+
+        let scores: Vec<f64> = population.iter()
+            .map(|e| {
+                let res = &fitted[index[e]].1;
+                let score = res.scores.get(config.criterion);
+                if res.reduced_chi2.is_finite() && !score.is_nan() { score } else { f64::INFINITY }
+            })
+            .collect();
+
+        population = breeder.next_generation(&population, &scores);
+    }
+
+    Ok(fitted)
+}
+
+/// Formulas of `EQUATIONS` entries with matching units, without duplicates.
+fn equation_seeds(unit_inputs: &[MksUnit], unit_outputs: &[MksUnit]) -> Vec<Expr> {
+    let mut seen: HashSet<Expr> = HashSet::new();
+    find_equation_by_units(unit_inputs, unit_outputs).into_iter()
+        .filter_map(|id| EQUATIONS[id].expr)
+        .filter_map(|text| text.parse::<Expr>().ok())
+        .filter(|e| seen.insert(e.clone()))
+        .collect()
+}
+
+/// Genetic operators drawing from one random stream.
+struct Breeder<'a> {
+    rng: Rng,
+    gp: &'a GpOptions,
+    nr_inp: usize,
+    nr_measurements: usize,
+    /// Units of inputs and output when trees are repaired
+    units: Option<(&'a [MksUnit], MksUnit)>,
+}
+
+impl Breeder<'_> {
+    /// Elite trees and children of tournament winners.
+    fn next_generation(&mut self, population: &[Expr], scores: &[f64]) -> Vec<Expr> {
+        let mut order: Vec<usize> = (0..population.len()).collect();
+        order.sort_by(|a, b| cmp_nan_last(scores[*a], scores[*b]));
+
+        let mut next: Vec<Expr> = order.iter().take(self.gp.elitism).map(|i| population[*i].clone()).collect();
+
+        for _ in 0..self.gp.population * MAX_ATTEMPTS {
+            if next.len() >= self.gp.population {
+                break;
+            }
+            let parent = &population[self.tournament(scores)];
+            let mut child = if self.rng.uniform() < self.gp.crossover_rate {
+                let other = &population[self.tournament(scores)];
+                self.crossover(parent, other)
+            } else {
+                parent.clone()
+            };
+            if self.rng.uniform() < self.gp.mutation_rate {
+                child = self.mutate(child);
+            }
+            next.extend(self.finish(child));
+        }
+
+        next
+    }
+
+    /// Index of the best of `tournament` randomly picked trees.
+    fn tournament(&mut self, scores: &[f64]) -> usize {
+        let mut best = self.rng.below(scores.len());
+        for _ in 1..self.gp.tournament {
+            let other = self.rng.below(scores.len());
+            if scores[other] < scores[best] {
+                best = other;
+            }
+        }
+        best
+    }
+
+    /// Replace random subtree of `a` with random subtree of `b`.
+    fn crossover(&mut self, a: &Expr, b: &Expr) -> Expr {
+        let donor = b.subtree(self.rng.below(b.size())).cloned();
+        let mut child = a.clone();
+        if let (Some(donor), Some(node)) = (donor, child.subtree_mut(self.rng.below(a.size()))) {
+            *node = donor;
+        }
+        child
+    }
+
+    /// Replace random subtree with new random tree.
+    fn mutate(&mut self, mut e: Expr) -> Expr {
+        let index = self.rng.below(e.size());
+        let depth = self.gp.max_depth.clamp(1, 2);
+        let tree = self.random_tree(depth);
+        if let Some(node) = e.subtree_mut(index) {
+            *node = tree;
+        }
+        e
+    }
+
+    /// Random tree of up to `depth` levels grown from all primitives.
+    fn random_tree(&mut self, depth: usize) -> Expr {
+        if depth <= 1 || self.rng.uniform() < LEAF_PROBABILITY {
+            return if self.nr_inp > 0 && self.rng.uniform() < INPUT_PROBABILITY {
+                Expr::Input(self.rng.below(self.nr_inp))
+            } else {
+                Expr::Const(0)
+            };
+        }
+        let op = self.rng.below(9);
+        let mut sub = || Box::new(self.random_tree(depth - 1));
+        match op {
+            0 => Expr::Add(sub(), sub()),
+            1 => Expr::Sub(sub(), sub()),
+            2 => Expr::Mul(sub(), sub()),
+            3 => Expr::Div(sub(), sub()),
+            4 => Expr::Pow(sub(), sub()),
+            5 => Expr::Sin(sub()),
+            6 => Expr::Exp(sub()),
+            7 => Expr::Log(sub()),
+            _ => Expr::Sqrt(sub()),
+        }
+    }
+
+    /// Repair units and number constants, reject trees too large,
+    /// without inputs or with more constants than measurements.
+    fn finish(&self, e: Expr) -> Option<Expr> {
+        let mut e = match self.units {
+            Some((inputs, output)) => repair_units(e, inputs, output),
+            None => e,
+        };
+        e.renumber_constants(&mut 0);
+        (e.size() <= self.gp.max_size && e.has_input() && e.nr_constants() <= self.nr_measurements).then_some(e)
+    }
+}
+
+/// Unit of subexpression, `None` if it has a constant factor which takes any unit.
+type Dim = Option<MksUnit>;
+
+/// Constructor of binary operation node
+type BinaryOp = fn(Box<Expr>, Box<Expr>) -> Expr;
+
+/// Make expression dimensionally consistent, constants may have any unit.
+///
+/// Operand of `+` or `-` with unlike unit, non-scalar argument of `sin`, `exp`,
+/// `log` or `pow`, argument of `sqrt` with odd unit powers and the whole tree
+/// with unit other than `output` are multiplied by a new constant.
+/// New constants are `c0`, renumber them before fitting.
+pub(crate) fn repair_units(e: Expr, inputs: &[MksUnit], output: MksUnit) -> Expr {
+    match repair(e, inputs) {
+        (e, Some(unit)) if unit != output => scaled(e),
+        (e, _) => e,
+    }
+}
+
+fn repair(e: Expr, inputs: &[MksUnit]) -> (Expr, Dim) {
+    match e {
+        Expr::Const(_) => (e, None),
+        Expr::Input(i) => (e, Some(inputs[i])),
+        Expr::Add(a, b) => repair_sum(Expr::Add, *a, *b, inputs),
+        Expr::Sub(a, b) => repair_sum(Expr::Sub, *a, *b, inputs),
+        Expr::Mul(a, b) => {
+            let ((a, ua), (b, ub)) = (repair(*a, inputs), repair(*b, inputs));
+            (Expr::Mul(Box::new(a), Box::new(b)), ua.zip(ub).map(|(ua, ub)| ua * ub))
+        },
+        Expr::Div(a, b) => {
+            let ((a, ua), (b, ub)) = (repair(*a, inputs), repair(*b, inputs));
+            (Expr::Div(Box::new(a), Box::new(b)), ua.zip(ub).map(|(ua, ub)| ua / ub))
+        },
+        Expr::Pow(a, b) => (Expr::Pow(Box::new(scalar(*a, inputs)), Box::new(scalar(*b, inputs))), Some(SCALAR_UNIT)),
+        Expr::Sin(a) => (Expr::Sin(Box::new(scalar(*a, inputs))), Some(SCALAR_UNIT)),
+        Expr::Exp(a) => (Expr::Exp(Box::new(scalar(*a, inputs))), Some(SCALAR_UNIT)),
+        Expr::Log(a) => (Expr::Log(Box::new(scalar(*a, inputs))), Some(SCALAR_UNIT)),
+        Expr::Sqrt(a) => match repair(*a, inputs) {
+            (a, None) => (Expr::Sqrt(Box::new(a)), None),
+            (a, Some(unit)) => match unit_sqrt(unit) {
+                Some(root) => (Expr::Sqrt(Box::new(a)), Some(root)),
+                None => (Expr::Sqrt(Box::new(scaled(a))), None),
+            },
+        },
+    }
+}
+
+/// Repair operands of `+` or `-`, second operand is scaled to unit of the first.
+fn repair_sum(op: BinaryOp, a: Expr, b: Expr, inputs: &[MksUnit]) -> (Expr, Dim) {
+    let ((a, ua), (b, ub)) = (repair(a, inputs), repair(b, inputs));
+    match (ua, ub) {
+        (Some(ua), Some(ub)) if ua != ub => (op(Box::new(a), Box::new(scaled(b))), Some(ua)),
+        _ => (op(Box::new(a), Box::new(b)), ua.or(ub)),
+    }
+}
+
+/// Repaired expression which unit is either scalar or free.
+fn scalar(e: Expr, inputs: &[MksUnit]) -> Expr {
+    match repair(e, inputs) {
+        (e, Some(unit)) if unit != SCALAR_UNIT => scaled(e),
+        (e, _) => e,
+    }
+}
+
+/// `c*e`
+fn scaled(e: Expr) -> Expr {
+    Expr::Mul(Box::new(Expr::Const(0)), Box::new(e))
+}
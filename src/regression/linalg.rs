@@ -0,0 +1,65 @@
+//! Small dense linear algebra for fitting.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! Matrices are row-major `n x n` slices.
+
+/// Solve `A*x = b` by Gaussian elimination with partial pivoting.
+///
+/// On return `b` holds solution `x`, `a` is destroyed.
+/// Return `false` if matrix is singular.
+pub fn solve(a: &mut [f64], b: &mut [f64], n: usize) -> bool {
+    for col in 0..n {
+        let pivot_row = (col..n)
+            .max_by(|i, j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))
+            .unwrap_or(col);
+        let pivot = a[pivot_row * n + col];
+        if pivot == 0.0 || !pivot.is_finite() {
+            return false;
+        }
+        if pivot_row != col {
+            for k in 0..n {
+                a.swap(col * n + k, pivot_row * n + k);
+            }
+            b.swap(col, pivot_row);
+        }
+        for row in col + 1..n {
+            let factor = a[row * n + col] / pivot;
+            if factor == 0.0 {
+                continue;
+            }
+            for k in col..n {
+                a[row * n + k] -= factor * a[col * n + k];
+            }
+            b[row] -= factor * b[col];
+        }
+    }
+
+    for row in (0..n).rev() {
+        let mut sum = b[row];
+        for k in row + 1..n {
+            sum -= a[row * n + k] * b[k];
+        }
+        b[row] = sum / a[row * n + row];
+    }
+
+    b.iter().all(|x| x.is_finite())
+}
+
+/// Inverse of `n x n` matrix, `None` if matrix is singular.
+pub fn invert(a: &[f64], n: usize) -> Option<Vec<f64>> {
+    let mut inverse = vec![0.0_f64; n * n];
+    for col in 0..n {
+        let mut m = a.to_vec();
+        let mut e = vec![0.0_f64; n];
+        e[col] = 1.0;
+        if !solve(&mut m, &mut e, n) {
+            return None;
+        }
+        for row in 0..n {
+            inverse[row * n + col] = e[row];
+        }
+    }
+    Some(inverse)
+}
@@ -0,0 +1,65 @@
+//! Robust loss functions of normalized residuals.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Robust_regression>
+//! - <https://en.wikipedia.org/wiki/Iteratively_reweighted_least_squares>
+//!
+
+/// Normalized residuals below this are treated as this in L1 weights.
+const L1_MIN_RESIDUAL: f64 = 1.0e-6;
+
+/// Loss of normalized residual `r = (Oᵢ - fᵢ)/sigmaᵢ` minimized by fitting.
+///
+/// Robust losses grow slower than `r²` for large residuals,
+/// so single outliers do not dominate the fit.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{FitOptions, Loss};
+/// let options = FitOptions { loss: Loss::Huber(1.5), ..FitOptions::default() };
+/// assert_eq!(FitOptions::default().loss, Loss::Chi2);
+/// assert_eq!(Loss::Huber(1.5).deviance(3.0), 2.0 * 1.5 * (3.0 - 0.75));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Loss {
+    /// Least squares `r²/2`, i.e. plain χ²
+    Chi2,
+    /// Huber loss, quadratic up to given threshold and linear beyond
+    Huber(f64),
+    /// Cauchy (Lorentzian) loss `(c²/2)*ln(1 + (r/c)²)` with given scale c
+    Cauchy(f64),
+    /// Absolute residual `|r|`
+    L1,
+}
+
+impl Loss {
+    /// Loss ρ(r).
+    pub fn rho(&self, r: f64) -> f64 {
+        match *self {
+            Loss::Chi2 => r * r / 2.0,
+            Loss::Huber(c) => if r.abs() <= c { r * r / 2.0 } else { c * (r.abs() - c / 2.0) },
+            Loss::Cauchy(c) => c * c / 2.0 * (r / c).powi(2).ln_1p(),
+            Loss::L1 => r.abs(),
+        }
+    }
+
+    /// Deviance `2ρ(r)`, equals `r²` for χ² loss.
+    pub fn deviance(&self, r: f64) -> f64 {
+        2.0 * self.rho(r)
+    }
+
+    /// Weight `ρ'(r)/r` of squared residual in iteratively reweighted least squares.
+    pub fn weight(&self, r: f64) -> f64 {
+        match *self {
+            Loss::Chi2 => 1.0,
+            Loss::Huber(c) => if r.abs() <= c { 1.0 } else { c / r.abs() },
+            Loss::Cauchy(c) => 1.0 / (1.0 + (r / c).powi(2)),
+            Loss::L1 => 1.0 / r.abs().max(L1_MIN_RESIDUAL),
+        }
+    }
+}
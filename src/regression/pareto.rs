@@ -0,0 +1,51 @@
+//! Pareto front of fit error versus complexity.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Pareto_front>
+//!
+use super::expr::Expr;
+
+/// Candidate formula of regression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Candidate {
+    /// Index of equation in `EQUATIONS`
+    Equation(usize),
+    /// Expression tree built from primitives
+    Expression(Expr),
+}
+
+/// Candidate on the Pareto front.
+#[derive(Debug, Clone)]
+pub struct ParetoPoint {
+    /// Equation or expression
+    pub candidate: Candidate,
+    /// Fitted constants
+    pub constants: Vec<f64>,
+    /// χ² of the fit
+    pub chi2: f64,
+    /// Reduced χ² of the fit
+    pub reduced_chi2: f64,
+    /// Number of nodes in expression tree
+    pub complexity: usize,
+}
+
+/// Keep only candidates not dominated by a simpler or equally simple candidate with smaller χ².
+///
+/// Returned points are sorted by increasing complexity and decreasing χ².
+pub fn pareto_front(mut points: Vec<ParetoPoint>) -> Vec<ParetoPoint> {
+    points.retain(|p| p.chi2.is_finite());
+    points.sort_by(|a, b| a.complexity.cmp(&b.complexity).then(a.chi2.total_cmp(&b.chi2)));
+
+    let mut front: Vec<ParetoPoint> = Vec::new();
+    for point in points {
+        if front.last().is_none_or(|best| point.chi2 < best.chi2) {
+            front.push(point);
+        }
+    }
+
+    front
+}
@@ -0,0 +1,121 @@
+//! Bounded work-stealing pool of worker threads.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Work_stealing>
+//!
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Callback with number of finished tasks and total number of tasks.
+pub type Progress = Arc<dyn Fn(usize, usize) + Send + Sync>;
+
+/// Options of the worker pool running regression candidates.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::sync::atomic::AtomicBool;
+/// use rustamath_physics::{FitOptions, PoolOptions};
+/// let cancel = Arc::new(AtomicBool::new(false));
+/// let pool = PoolOptions {
+///     threads: Some(2),
+///     cancel: Some(cancel.clone()),
+///     progress: Some(Arc::new(|done, total| println!("{done}/{total}"))),
+/// };
+/// let options = FitOptions { pool, ..FitOptions::default() };
+/// ```
+#[derive(Clone, Default)]
+pub struct PoolOptions {
+    /// Number of worker threads, `available_parallelism` when `None`
+    pub threads: Option<usize>,
+    /// Workers stop taking new tasks once it is set,
+    /// results of finished tasks are still returned
+    pub cancel: Option<Arc<AtomicBool>>,
+    /// Called by workers after every finished task
+    pub progress: Option<Progress>,
+}
+
+impl fmt::Debug for PoolOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PoolOptions")
+            .field("threads", &self.threads)
+            .field("cancel", &self.cancel)
+            .field("progress", &self.progress.as_ref().map(|_| "Fn"))
+            .finish()
+    }
+}
+
+/// Apply function to every item in parallel, return results in order of items.
+///
+/// Items are split into contiguous queues, one per worker; a worker that
+/// runs out of own tasks steals from the back of other queues.
+/// Results of tasks skipped because of cancellation are missing.
+pub fn map<T, R, F>(items: &[T], options: &PoolOptions, fun: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    use std::thread;
+
+    let nr_tasks = items.len();
+    let nr_workers = options.threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+        .clamp(1, nr_tasks.max(1));
+
+    let chunk_size = nr_tasks.div_ceil(nr_workers).max(1);
+    let queues: Vec<Mutex<VecDeque<usize>>> = (0..nr_workers)
+        .map(|w| Mutex::new((w * chunk_size..((w + 1) * chunk_size).min(nr_tasks)).collect()))
+        .collect();
+
+    let nr_done = AtomicUsize::new(0);
+    let mut results: Vec<(usize, R)> = Vec::with_capacity(nr_tasks);
+
+    thread::scope(|thread_scope| {
+        let mut ths = Vec::new();
+
+        for worker in 0..nr_workers {
+            let (queues, nr_done, fun) = (&queues, &nr_done, &fun);
+            let th = thread_scope.spawn(move || {
+                let mut done: Vec<(usize, R)> = Vec::new();
+                while let Some(task) = next_task(queues, worker) {
+                    if options.cancel.as_ref().is_some_and(|cancel| cancel.load(Ordering::Relaxed)) {
+                        break;
+                    }
+                    done.push((task, fun(&items[task])));
+                    let nr_finished = nr_done.fetch_add(1, Ordering::Relaxed) + 1;
+                    if let Some(progress) = &options.progress {
+                        progress(nr_finished, nr_tasks);
+                    }
+                }
+                done
+            });
+            ths.push(th);
+        }
+
+        for th in ths {
+            let mut done = th.join().unwrap();
+            results.append(&mut done);
+        }
+    });
+
+    results.sort_by_key(|(task, _)| *task);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Pop task from front of own queue or steal one from back of another queue.
+fn next_task(queues: &[Mutex<VecDeque<usize>>], worker: usize) -> Option<usize> {
+    if let Some(task) = queues[worker].lock().unwrap().pop_front() {
+        return Some(task);
+    }
+    (1..queues.len())
+        .map(|offset| (worker + offset) % queues.len())
+        .find_map(|victim| queues[victim].lock().unwrap().pop_back())
+}
@@ -0,0 +1,70 @@
+//! Deterministic pseudo-random numbers for stochastic search.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://prng.di.unimi.it/splitmix64.c>
+//!
+
+/// SplitMix64 pseudo-random generator, same seed gives same sequence.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// New generator with given seed.
+    pub fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    /// Next 64 random bits.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`.
+    pub fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1_u64 << 53) as f64)
+    }
+
+    /// Uniform index in `[0, n)`, `n` must be positive.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.uniform() * n as f64) as usize % n
+    }
+
+    /// Random permutation in place (Fisher-Yates).
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = self.below(i + 1);
+            items.swap(i, j);
+        }
+    }
+}
+
+/// Seed of independent random stream derived from parent seed.
+pub fn derive_seed(seed: u64, stream: u64) -> u64 {
+    Rng::new(seed ^ stream.wrapping_mul(0xd1b5_4a32_d192_ed03)).next_u64()
+}
+
+/// Latin hypercube sample of `nr_points` points inside the box, one point per stratum along every axis.
+pub fn latin_hypercube(rng: &mut Rng, bounds: &[(f64, f64)], nr_points: usize) -> Vec<Vec<f64>> {
+    let mut points = vec![vec![0.0_f64; bounds.len()]; nr_points];
+
+    for (j, (lo, hi)) in bounds.iter().enumerate() {
+        let mut strata: Vec<usize> = (0..nr_points).collect();
+        rng.shuffle(&mut strata);
+        for (point, stratum) in points.iter_mut().zip(strata.iter()) {
+            let u = (*stratum as f64 + rng.uniform()) / nr_points as f64;
+            point[j] = lo + u * (hi - lo);
+        }
+    }
+
+    points
+}
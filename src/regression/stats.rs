@@ -0,0 +1,87 @@
+//! Statistical functions for goodness-of-fit.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! 1. William H. Press - Numerical recipes, the art of scientific computing.
+//!    Cambridge University Press (2007).
+
+/// Logarithm of Gamma function, Lanczos approximation.
+pub fn ln_gamma(x: f64) -> f64 {
+    const COF: [f64; 14] = [
+        57.156_235_665_862_92, -59.597_960_355_475_49, 14.136_097_974_741_746,
+        -0.491_913_816_097_620_2, 0.339_946_499_848_118_9e-4, 0.465_236_289_270_485_7e-4,
+        -0.983_744_753_048_795_2e-4, 0.158_088_703_224_912_5e-3, -0.210_264_441_724_104_9e-3,
+        0.217_439_618_115_212_6e-3, -0.164_318_106_536_763_9e-3, 0.844_182_239_838_527_5e-4,
+        -0.261_908_384_015_814_1e-4, 0.368_991_826_595_316_2e-5];
+    let tmp = x + 5.242_187_5;
+    let tmp = (x + 0.5) * tmp.ln() - tmp;
+    let mut ser = 0.999_999_999_999_997_1;
+    let mut y = x;
+    for c in COF.iter() {
+        y += 1.0;
+        ser += c / y;
+    }
+    tmp + (2.506_628_274_631_000_5 * ser / x).ln()
+}
+
+/// Incomplete Gamma function `Q(a, x) = 1 - P(a, x)`.
+pub fn gamma_q(a: f64, x: f64) -> f64 {
+    if x <= 0.0 || a <= 0.0 {
+        return 1.0;
+    }
+    if x < a + 1.0 {
+        1.0 - gamma_p_series(a, x)
+    }
+    else {
+        gamma_q_continued_fraction(a, x)
+    }
+}
+
+fn gamma_p_series(a: f64, x: f64) -> f64 {
+    let mut ap = a;
+    let mut del = 1.0 / a;
+    let mut sum = del;
+    for _ in 0..1000 {
+        ap += 1.0;
+        del *= x / ap;
+        sum += del;
+        if del.abs() < sum.abs() * f64::EPSILON {
+            break;
+        }
+    }
+    sum * (-x + a * x.ln() - ln_gamma(a)).exp()
+}
+
+fn gamma_q_continued_fraction(a: f64, x: f64) -> f64 {
+    const FPMIN: f64 = f64::MIN_POSITIVE / f64::EPSILON;
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / FPMIN;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..1000 {
+        let an = -(i as f64) * (i as f64 - a);
+        b += 2.0;
+        d = an * d + b;
+        if d.abs() < FPMIN { d = FPMIN; }
+        c = b + an / c;
+        if c.abs() < FPMIN { c = FPMIN; }
+        d = 1.0 / d;
+        let del = d * c;
+        h *= del;
+        if (del - 1.0).abs() <= f64::EPSILON {
+            break;
+        }
+    }
+    (-x + a * x.ln() - ln_gamma(a)).exp() * h
+}
+
+/// Probability that χ² this large or larger occurs by chance for a correct model.
+pub fn chi2_p_value(chi2: f64, degrees_of_freedom: usize) -> f64 {
+    if chi2.is_nan() {
+        return f64::NAN;
+    }
+    gamma_q(degrees_of_freedom as f64 / 2.0, chi2 / 2.0)
+}
@@ -0,0 +1,32 @@
+use crate::*;
+use super::Model;
+use super::expr::ExprModel;
+use std::rc::Rc;
+
+#[test]
+fn test_batch_matches_run() {
+    for (id, eq) in EQUATIONS.iter().enumerate() {
+        let (out, cns, inp) = (eq.params)();
+        let constants: Vec<f64> = (0..cns.len()).map(|i| 1.5 + i as f64).collect();
+        let inputs: Vec<f64> = (0..5 * inp.len()).map(|i| 0.25 + i as f64 * 0.5).collect();
+
+        let mut equation = (eq.new)(&constants);
+        let expected: Vec<f64> = inputs.chunks(inp.len()).flat_map(|x| equation.run(x)).collect();
+
+        let mut outputs = vec![f64::NAN; 5 * out.len()];
+        equation.run_batch(&inputs, &mut outputs);
+        assert_eq!(outputs, expected, "#{} {}", id, eq.desc);
+    }
+}
+
+#[test]
+fn test_expr_batch() {
+    let expr: Expr = "c0*sin(c1*x0) + x1".parse().unwrap();
+    let mut equation = ExprModel { expr: Rc::new(expr), nr_inp: 2 }.make(&[2.0, 0.5]);
+    let inputs = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let mut outputs = [0.0; 3];
+    equation.run_batch(&inputs, &mut outputs);
+    for (x, y) in inputs.chunks(2).zip(outputs.iter()) {
+        assert_eq!(*y, equation.run(x)[0]);
+    }
+}
@@ -0,0 +1,86 @@
+use crate::*;
+use rustamath_mks::*;
+use super::units::{unit_exponents, unit_pow};
+
+#[test]
+fn test_unit_exponents() {
+    let base = [DISTANCE_UNIT, KILOGRAM_UNIT, TIME_UNIT, AMPERE_UNIT];
+    for unit in [SCALAR_UNIT, VELOCITY_UNIT, ACCEL_UNIT, JOULE_UNIT, NEWTON_UNIT, VACUUM_PERMITTIVITY_UNIT, TIME_UNIT / AREA_UNIT] {
+        let exponents = unit_exponents(unit).unwrap();
+        let rebuilt = base.iter().zip(exponents.iter())
+            .fold(SCALAR_UNIT, |u, (b, e)| u * unit_pow(*b, *e as i8));
+        assert_eq!(rebuilt, unit, "{}", unit);
+    }
+    assert_eq!(unit_exponents(TIME_UNIT / AREA_UNIT), Some([-2, 0, 1, 0]));
+    assert_eq!(unit_exponents(unit_pow(DISTANCE_UNIT, 9)), None);
+}
+
+#[test]
+fn test_unit_exponents_of_equations() {
+    let base = [DISTANCE_UNIT, KILOGRAM_UNIT, TIME_UNIT, AMPERE_UNIT];
+    for eq in EQUATIONS.iter() {
+        let (out, cns, inp) = (eq.params)();
+        for unit in out.iter().chain(cns.iter()).chain(inp.iter()) {
+            let rebuilt = base.iter().zip(unit_exponents(*unit).unwrap().iter())
+                .fold(SCALAR_UNIT, |u, (b, e)| u * unit_pow(*b, *e as i8));
+            assert_eq!(rebuilt, *unit, "{}: {}", eq.desc, unit);
+        }
+    }
+}
+
+#[test]
+fn test_groups_are_dimensionless() {
+    let units = [VELOCITY_UNIT, TIME_UNIT, ACCEL_UNIT, KILOGRAM_UNIT, JOULE_UNIT, DISTANCE_UNIT];
+    let groups = pi_groups(&units);
+    // 6 variables of rank 3 (m, kg, s)
+    assert_eq!(groups.len(), 3);
+    for g in groups.iter() {
+        let unit = units.iter().zip(g.exponents.iter())
+            .fold(SCALAR_UNIT, |u, (v, e)| u * unit_pow(*v, *e as i8));
+        assert_eq!(unit, SCALAR_UNIT);
+    }
+}
+
+#[test]
+fn test_transform() {
+    let problem = DimensionlessProblem::new(&[VELOCITY_UNIT, TIME_UNIT, ACCEL_UNIT], DISTANCE_UNIT).unwrap();
+    let inputs = [2.0, 3.0, 4.0];
+    let outputs = [2.0 * 3.0 + 4.0 * 9.0 / 2.0];
+    let (pi_inputs, pi_outputs) = problem.transform(&inputs, &outputs);
+    assert_eq!(pi_inputs, vec![4.0 * 3.0 / 2.0]);
+    assert_eq!(pi_outputs, vec![24.0 / 6.0]);
+    assert!((problem.output_value(&inputs, pi_outputs[0]) - outputs[0]).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_find_distance_law() {
+    // `s = v0*t + a*t^2/2` becomes `s/(v0*t) = 1 + (a*t/v0)/2`
+    let mut inputs: Vec<f64> = Vec::new();
+    for i in 1..6 {
+        for j in 1..4 {
+            inputs.extend([i as f64, j as f64 * 0.5, (i + j) as f64 * 0.3]);
+        }
+    }
+    let outputs: Vec<f64> = inputs.chunks(3).map(|x| x[0] * x[1] + x[2] * x[1] * x[1] / 2.0).collect();
+
+    let unit_inputs = [VELOCITY_UNIT, TIME_UNIT, ACCEL_UNIT];
+    let exprs = find_expression_dimensionless(&unit_inputs, &[DISTANCE_UNIT], &inputs, &outputs, &ExprSearch::default()).unwrap();
+
+    for (i, e) in exprs.iter().enumerate() {
+        println!("#{}: fit = {:10.6} {} {:?}", i+1, e.chi2, e.expr, e.constants);
+    }
+    let best = &exprs[0];
+    assert!(best.chi2 < 1.0e-12);
+    for (x, y) in inputs.chunks(3).zip(outputs.iter()) {
+        assert!((best.expr.eval(x, &best.constants) - y).abs() < 1.0e-6);
+    }
+}
+
+#[test]
+fn test_not_dimensionless() {
+    let res = find_expression_dimensionless(&[TIME_UNIT], &[DISTANCE_UNIT], &[1.0, 2.0], &[1.0, 2.0], &ExprSearch::default());
+    assert_eq!(res.err(), Some(Error::NotDimensionless));
+
+    let res = find_expression_dimensionless(&[TIME_UNIT], &[DISTANCE_UNIT, VELOCITY_UNIT], &[1.0, 2.0], &[1.0, 2.0, 3.0, 4.0], &ExprSearch::default());
+    assert_eq!(res.err(), Some(Error::OutputUnitCount { expected: 1, actual: 2 }));
+}
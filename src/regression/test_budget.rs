@@ -0,0 +1,64 @@
+use crate::*;
+use rustamath_mks::*;
+use std::time::Duration;
+
+fn data() -> (Vec<f64>, Vec<f64>) {
+    let inputs: Vec<f64> = (1..8).map(|i| i as f64).collect();
+    let outputs: Vec<f64> = inputs.iter().map(|x| 2.0 * x).collect();
+    (inputs, outputs)
+}
+
+fn single_thread() -> FitOptions {
+    FitOptions { pool: PoolOptions { threads: Some(1), ..PoolOptions::default() }, ..FitOptions::default() }
+}
+
+#[test]
+fn test_evaluation_budget() {
+    let (inputs, outputs) = data();
+
+    let all = ExprSearch { max_results: usize::MAX, ..ExprSearch::default() };
+    let nr_all = find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &all).len();
+
+    let budget = Budget { evaluations: Some(4), ..Budget::default() };
+    let search = ExprSearch { budget, ..all };
+    let exprs = find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &search);
+    assert!(exprs.len() <= 4 && exprs.len() < nr_all);
+    // Smallest expressions are fitted first
+    assert!(exprs.iter().all(|e| e.expr.size() <= 3));
+
+    // Only the first equation in `EQUATIONS` order
+    let config = RegressionConfig { budget: Budget { evaluations: Some(1), ..Budget::default() }, ..RegressionConfig::default() };
+    let eqs = find_equation_seeded(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &[], &config);
+    assert_eq!(eqs.len(), 1);
+    assert_eq!(eqs[0].0, find_equation_by_units(&[TIME_UNIT], &[DISTANCE_UNIT])[0]);
+}
+
+#[test]
+fn test_early_stopping() {
+    let (inputs, outputs) = data();
+
+    let all = ExprSearch { max_results: usize::MAX, fit: single_thread(), ..ExprSearch::default() };
+    let nr_all = find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &all).len();
+
+    let budget = Budget { target_reduced_chi2: Some(1.0e-9), ..Budget::default() };
+    let search = ExprSearch { budget, ..all };
+    let exprs = find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &search);
+
+    assert!(exprs.len() < nr_all);
+    assert!(exprs[0].chi2 <= 1.0e-9);
+    assert_eq!(exprs[0].expr.to_string(), "c0*x0");
+}
+
+#[test]
+fn test_time_budget() {
+    let (inputs, outputs) = data();
+
+    let budget = Budget { time: Some(Duration::ZERO), ..Budget::default() };
+    let search = ExprSearch { budget, ..ExprSearch::default() };
+    assert!(find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &search).is_empty());
+
+    let budget = Budget { time: Some(Duration::from_secs(3600)), ..Budget::default() };
+    let search = ExprSearch { budget, ..ExprSearch::default() };
+    let exprs = find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &search);
+    assert!(exprs[0].chi2 < 1.0e-9);
+}
@@ -0,0 +1,39 @@
+use crate::*;
+use rustamath_mks::*;
+
+#[test]
+fn test_scores() {
+    let scores = Scores::new(4.0, 1.0, 6, 2, 5, true);
+    assert_eq!(scores.aic, 4.0 + 4.0);
+    assert_eq!(scores.aicc, 8.0 + 12.0 / 3.0);
+    assert!((scores.bic - (4.0 + 2.0 * 6.0_f64.ln())).abs() < 1.0e-12);
+    assert!((scores.mdl - (2.0 + 6.0_f64.ln() + 5.0 * 16.0_f64.ln())).abs() < 1.0e-12);
+    assert_eq!(scores.get(Criterion::ReducedChi2), 1.0);
+
+    // Unknown sigmas, misfit is `N*ln(χ²/N)`
+    let scores = Scores::new(6.0, 1.5, 6, 2, 5, false);
+    assert!((scores.aic - 4.0).abs() < 1.0e-12);
+
+    // Too few measurements for AICc
+    assert_eq!(Scores::new(0.0, 0.0, 3, 2, 5, true).aicc, f64::INFINITY);
+}
+
+#[test]
+fn test_rank_by_criterion() {
+    // `s = v*t` with v=3.0 and measurement noise
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let noise: [f64; 6] = [0.05, -0.08, 0.01, -0.06, 0.07, 0.06];
+    let outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(t, e)| 3.0 * t + e).collect();
+
+    let uniform = get_equation_by_typeid(mechanics::linear_motion::const_velocity::DistanceEquation::params).unwrap();
+
+    let eqs = find_equation_ranked(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs,
+        &[0.1], &FitOptions::default(), Criterion::ReducedChi2);
+    assert_ne!(eqs[0].0, uniform);
+
+    for criterion in [Criterion::Aic, Criterion::Aicc, Criterion::Bic, Criterion::Mdl] {
+        let eqs = find_equation_ranked(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs,
+            &[0.1], &FitOptions::default(), criterion);
+        assert_eq!(eqs[0].0, uniform, "{:?}", criterion);
+    }
+}
@@ -0,0 +1,74 @@
+use crate::*;
+use rustamath_mks::*;
+
+#[test]
+fn test_try_make_and_run() {
+    for eq in EQUATIONS.iter() {
+        let (out, cns, inp) = (eq.params)();
+
+        let wrong_cns = vec![1.0; cns.len() + 1];
+        assert_eq!(eq.try_make(&wrong_cns).err(), Some(Error::ConstantLength { expected: cns.len(), actual: cns.len() + 1 }));
+
+        let mut equation = eq.try_make(&vec![1.0; cns.len()]).unwrap();
+        assert_eq!(equation.nr_inputs(), inp.len());
+        assert_eq!(equation.try_run(&vec![1.0; inp.len()]).unwrap().len(), out.len());
+        assert_eq!(equation.try_run(&[]), Err(Error::InputLength { expected: inp.len(), actual: 0 }));
+    }
+}
+
+#[test]
+fn test_try_goodness_of_fit() {
+    let id = get_equation_by_typeid(mechanics::linear_motion::const_accel::VelocityEquation::params).unwrap();
+    let inputs = [0.0, 1.0, 2.0];
+
+    assert!(try_goodness_of_fit(id, &inputs, &[3.0, 5.0, 7.0], &[0.1]).unwrap() < 1.0e-6);
+    assert_eq!(try_goodness_of_fit(id, &inputs, &[3.0, 5.0], &[]),
+        Err(Error::DataLength { nr_inputs: 1, nr_outputs: 1, inputs_len: 3, outputs_len: 2 }));
+
+    // Two outputs per measurement
+    let id = get_equation_by_typeid(mechanics::linear_motion::const_accel::DistanceVelocityEquation::params).unwrap();
+    assert!(try_goodness_of_fit(id, &inputs, &[0.0, 3.0, 4.0, 5.0, 10.0, 7.0], &[0.1, 0.1]).is_ok());
+    assert!(try_goodness_of_fit(id, &inputs, &[0.0, 3.0, 4.0, 5.0, 10.0], &[]).is_err());
+
+    let err = try_find_equation(&[TIME_UNIT], &[VELOCITY_UNIT], &[], &[1.0]).unwrap_err();
+    assert_eq!(err.to_string(),
+        "0 input values with 1 inputs per measurement do not match 1 output values with 1 outputs per measurement");
+}
+
+#[test]
+fn test_nan_sorted_last() {
+    use super::cmp_nan_last;
+
+    let mut scores = [f64::NAN, 2.0, -f64::NAN, 1.0, f64::INFINITY];
+    scores.sort_by(|a, b| cmp_nan_last(*a, *b));
+    assert_eq!(scores[..3], [1.0, 2.0, f64::INFINITY]);
+    assert!(scores[3].is_nan() && scores[4].is_nan());
+
+    // Undefined data does not crash the search
+    let inputs = [0.0, 1.0, f64::NAN];
+    let eqs = find_equation(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &[0.0, 3.0, 7.0]);
+    assert_eq!(eqs.len(), find_equation_by_units(&[TIME_UNIT], &[DISTANCE_UNIT]).len());
+}
+
+#[test]
+fn test_try_find_expression() {
+    let inputs = [1.0, 2.0, 3.0];
+    let outputs = [2.0, 4.0, 6.0];
+
+    assert!(try_find_expression_with(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &ExprSearch::default()).is_ok());
+
+    // Model without inputs
+    let err = Error::DataLength { nr_inputs: 0, nr_outputs: 1, inputs_len: 0, outputs_len: 3 };
+    assert_eq!(try_find_expression_with(&[], &[SCALAR_UNIT], &[], &outputs, &ExprSearch::default()).err(), Some(err.clone()));
+    assert_eq!(try_find_expression_exhaustive(&[], &[SCALAR_UNIT], &[], &outputs, &[], &ExhaustiveSearch::default()).err(), Some(err.clone()));
+    let gp = GpOptions { generations: 1, ..GpOptions::default() };
+    assert_eq!(try_find_expression_gp(&[], &[SCALAR_UNIT], &[], &outputs, &[], &gp, &RegressionConfig::default()).err(), Some(err));
+
+    let two = [SCALAR_UNIT, SCALAR_UNIT];
+    let err = Error::OutputUnitCount { expected: 1, actual: 2 };
+    assert_eq!(try_find_expression_exhaustive(&[SCALAR_UNIT], &two, &inputs, &[0.0; 6], &[], &ExhaustiveSearch::default()).err(), Some(err.clone()));
+    assert_eq!(try_find_expression_gp(&[SCALAR_UNIT], &two, &inputs, &[0.0; 6], &[], &gp, &RegressionConfig::default()).err(), Some(err));
+
+    assert_eq!(try_find_expression_exhaustive(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[0.1; 2], &ExhaustiveSearch::default()).err(),
+        Some(Error::SigmaLength { expected: 3, actual: 2 }));
+}
@@ -0,0 +1,60 @@
+use crate::*;
+use rustamath_mks::*;
+use super::expr::{dedupe, enumerate_to_depth, UnitGrammar};
+
+#[test]
+fn test_canonical() {
+    let canonical = |text: &str| text.parse::<Expr>().unwrap().canonical().to_string();
+    assert_eq!(canonical("x1*x0"), canonical("x0*x1"));
+    assert_eq!(canonical("c1 + x0 - c0"), "c0 + x0");
+    assert_eq!(canonical("x0/c0*c1"), "c0*x0");
+    assert_eq!(canonical("(x0 + c0) + (x1 + c1)"), "c0 + x0 + x1");
+    assert_eq!(canonical("sin(c0*x0) + exp(c1)"), "c0 + sin(c1*x0)");
+    // Not folded across different operations
+    assert_eq!(canonical("c0*(x0 + c1)"), "c0*(c1 + x0)");
+}
+
+#[test]
+fn test_enumerate_to_depth() {
+    let grammar = UnitGrammar { inputs: &[SCALAR_UNIT, SCALAR_UNIT], constants: &[SCALAR_UNIT], output: SCALAR_UNIT };
+    let exprs = enumerate_to_depth(&grammar, 3, 7);
+
+    assert!(exprs.iter().all(|e| e.depth() <= 3 && e.size() <= 7));
+    assert!(exprs.windows(2).all(|w| w[0].size() <= w[1].size()));
+    assert!(exprs.iter().any(|e| e.to_string() == "c0 + x0*x1"));
+
+    let unique = dedupe(exprs.clone(), 2);
+    println!("{} expressions, {} unique", exprs.len(), unique.len());
+    assert!(unique.len() < exprs.len());
+    // Canonical forms of kept expressions are all different
+    let mut forms: Vec<Expr> = unique.iter().map(|e| e.canonical()).collect();
+    forms.sort_by_key(|e| e.to_string());
+    forms.dedup();
+    assert_eq!(forms.len(), unique.len());
+}
+
+#[test]
+fn test_dimensional_pruning() {
+    let grammar = UnitGrammar { inputs: &[TIME_UNIT], constants: &[VELOCITY_UNIT, ACCEL_UNIT], output: DISTANCE_UNIT };
+    let exprs = enumerate_to_depth(&grammar, 3, 9);
+
+    // `sin`, `exp` and `log` of time are never built
+    assert!(exprs.iter().all(|e| !e.to_string().contains("sin(x0")));
+    assert!(exprs.iter().any(|e| e.canonical().to_string() == "c0*x0*x0"));
+}
+
+#[test]
+fn test_find_two_inputs() {
+    // `y = 3*x0*x1 + 1`
+    let inputs: Vec<f64> = (1..10).flat_map(|i| [i as f64 * 0.5, (i % 3) as f64 + 1.0]).collect();
+    let outputs: Vec<f64> = inputs.chunks(2).map(|x| 3.0 * x[0] * x[1] + 1.0).collect();
+
+    let search = ExhaustiveSearch { max_depth: 4, max_size: 7, criterion: Criterion::Bic, ..ExhaustiveSearch::default() };
+    let exprs = find_expression_exhaustive(&[SCALAR_UNIT, SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &search);
+
+    for (i, e) in exprs.iter().enumerate() {
+        println!("#{}: fit = {:10.6} {} {:?}", i+1, e.chi2, e.expr, e.constants);
+    }
+    assert!(exprs[0].chi2 < 1.0e-9);
+    assert!((exprs[0].expr.eval(&[2.0, 2.0], &exprs[0].constants) - 13.0).abs() < 1.0e-6);
+}
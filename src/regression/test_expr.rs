@@ -0,0 +1,108 @@
+use crate::*;
+use rustamath_mks::*;
+use super::expr::enumerate;
+
+#[test]
+fn test_enumerate() {
+    let exprs = enumerate(1, 4);
+    assert!(!exprs.is_empty());
+    for e in exprs.iter() {
+        assert!(e.has_input());
+        assert!(e.size() <= 4);
+    }
+    // `c0*x0` is there, but not `x0*c0`
+    let c_x = Expr::Mul(Box::new(Expr::Const(0)), Box::new(Expr::Input(0)));
+    let x_c = Expr::Mul(Box::new(Expr::Input(0)), Box::new(Expr::Const(0)));
+    assert_eq!(exprs.iter().filter(|e| **e == c_x || **e == x_c).count(), 1);
+}
+
+#[test]
+fn test_linear_expression() {
+    let inputs: [f64; 8] = [0.5, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0, 6.5];
+    let outputs: Vec<f64> = inputs.iter().map(|x| 2.5 * x + 1.0).collect();
+
+    let exprs = find_expression(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs);
+
+    for (i, e) in exprs.iter().enumerate() {
+        println!("#{}: fit = {:10.6} {} {:?}", i+1, e.chi2, e.expr, e.constants);
+    }
+
+    let best = &exprs[0];
+    assert!(best.chi2 < 1.0e-3);
+    for (input, output) in inputs.iter().zip(outputs.iter()) {
+        assert!((best.expr.eval(&[*input], &best.constants) - output).abs() < 0.1);
+    }
+}
+
+#[test]
+fn test_two_inputs_expression() {
+    // `y = x0*x1`, like area of rectangle
+    let inputs: [f64; 12] = [1.0, 2.0, 2.0, 3.0, 3.0, 1.5, 4.0, 0.5, 2.5, 2.5, 5.0, 1.0];
+    let outputs: Vec<f64> = inputs.chunks(2).map(|x| x[0] * x[1]).collect();
+
+    let search = ExprSearch { max_size: 3, max_results: 5, ..ExprSearch::default() };
+    let exprs = find_expression_with(&[DISTANCE_UNIT; 2], &[AREA_UNIT], &inputs, &outputs, &search);
+
+    let x0_x1 = Expr::Mul(Box::new(Expr::Input(0)), Box::new(Expr::Input(1)));
+    assert_eq!(exprs[0].expr, x0_x1);
+    assert!(exprs[0].chi2 < 1.0e-12);
+}
+
+#[test]
+fn test_enumerate_with_units() {
+    use super::expr::{enumerate_with_units, UnitGrammar};
+
+    let grammar = UnitGrammar { inputs: &[DISTANCE_UNIT, TIME_UNIT], constants: &[SCALAR_UNIT], output: VELOCITY_UNIT };
+    let exprs = enumerate_with_units(&grammar, 5);
+    let all_exprs = enumerate(2, 5);
+    println!("unit-consistent {} of {}", exprs.len(), all_exprs.len());
+    assert!(exprs.len() * 10 < all_exprs.len());
+
+    let x0_over_x1 = Expr::Div(Box::new(Expr::Input(0)), Box::new(Expr::Input(1)));
+    assert!(exprs.contains(&x0_over_x1));
+
+    for e in exprs.iter() {
+        let s = e.to_string();
+        assert!(!s.contains("sin") && !s.contains("exp") && !s.contains("log") && !s.contains("pow"), "{}", s);
+        assert!(!s.contains("x0 + x1") && !s.contains("x0 - x1"), "{}", s);
+    }
+}
+
+#[test]
+fn test_velocity_expression() {
+    // `v = v0 + a*t`
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|t| 3.0 + 2.0 * t).collect();
+
+    let search = ExprSearch { constant_units: Some(vec![VELOCITY_UNIT, ACCEL_UNIT]), ..ExprSearch::default() };
+    let exprs = find_expression_with(&[TIME_UNIT], &[VELOCITY_UNIT], &inputs, &outputs, &search);
+
+    for (i, e) in exprs.iter().enumerate() {
+        println!("#{}: fit = {:10.6} {} {:?}", i+1, e.chi2, e.expr, e.constants);
+    }
+
+    let v0_plus_a_t = Expr::Add(
+        Box::new(Expr::Const(0)),
+        Box::new(Expr::Mul(Box::new(Expr::Const(1)), Box::new(Expr::Input(0)))));
+    assert_eq!(exprs[0].expr, v0_plus_a_t);
+    assert!((exprs[0].constants[0] - 3.0).abs() < 1.0e-3);
+    assert!((exprs[0].constants[1] - 2.0).abs() < 1.0e-3);
+}
+
+#[test]
+fn test_divide_by_constant_with_units() {
+    // `t = x/v`, only constant unit is velocity, so `x*c0` can not stand in for `x/c0`
+    let inputs: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|x| x / 2.5).collect();
+
+    let search = ExprSearch { max_size: 3, constant_units: Some(vec![VELOCITY_UNIT]), ..ExprSearch::default() };
+    let exprs = find_expression_with(&[DISTANCE_UNIT], &[TIME_UNIT], &inputs, &outputs, &search);
+
+    let x0_over_c0 = Expr::Div(Box::new(Expr::Input(0)), Box::new(Expr::Const(0)));
+    assert_eq!(exprs[0].expr, x0_over_c0);
+    assert!((exprs[0].constants[0] - 2.5).abs() < 1.0e-3);
+
+    // Unit-less constants are closed under inversion, `x/c0` is left out for `c0*x`
+    let grammar = super::expr::UnitGrammar { inputs: &[SCALAR_UNIT], constants: &[SCALAR_UNIT], output: SCALAR_UNIT };
+    assert!(!super::expr::enumerate_with_units(&grammar, 3).contains(&x0_over_c0));
+}
@@ -0,0 +1,59 @@
+use crate::*;
+use rustamath_mks::*;
+use super::stats::{chi2_p_value, ln_gamma};
+
+#[test]
+fn test_chi2_p_value() {
+    // For 2 degrees of freedom Q(χ²) = exp(-χ²/2)
+    for chi2 in [0.5, 2.0, 5.0, 20.0] {
+        assert!((chi2_p_value(chi2, 2) - (-chi2 / 2.0_f64).exp()).abs() < 1.0e-12);
+    }
+    // Tabulated 5% critical values
+    assert!((chi2_p_value(3.841, 1) - 0.05).abs() < 1.0e-4);
+    assert!((chi2_p_value(18.307, 10) - 0.05).abs() < 1.0e-4);
+    assert!((ln_gamma(5.0) - 24.0_f64.ln()).abs() < 1.0e-12);
+}
+
+#[test]
+fn test_straight_line_uncertainties() {
+    // `v = v0 + a*t`, compare with closed form straight line fit
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: [f64; 6] = [3.05, 4.9, 7.1, 8.95, 11.0, 13.1];
+    let sigma = 0.1;
+
+    let eq_index = get_equation_by_typeid(
+        mechanics::linear_motion::const_accel::VelocityEquation::params).unwrap();
+    let options = FitOptions { method: FitMethod::LevenbergMarquardt, tolerance: 1.0e-12, ..FitOptions::default() };
+    let res = fit_equation(eq_index, &inputs, &outputs, &[sigma], &options);
+
+    let n = inputs.len() as f64;
+    let mean_t = inputs.iter().sum::<f64>() / n;
+    let stt: f64 = inputs.iter().map(|t| (t - mean_t) * (t - mean_t)).sum();
+    let sum_t2: f64 = inputs.iter().map(|t| t * t).sum();
+    let var_a = sigma * sigma / stt;
+    let var_v0 = sigma * sigma * sum_t2 / (n * stt);
+    let cov_v0_a = -sigma * sigma * mean_t / stt;
+
+    println!("{:?}", res);
+    assert_eq!(res.degrees_of_freedom, 4);
+    assert!((res.std_errors[0] - var_v0.sqrt()).abs() < 1.0e-6);
+    assert!((res.std_errors[1] - var_a.sqrt()).abs() < 1.0e-6);
+    assert!((res.covariance[1] - cov_v0_a).abs() < 1.0e-6);
+    assert!((res.covariance[2] - cov_v0_a).abs() < 1.0e-6);
+    assert!((res.p_value - chi2_p_value(res.chi2, 4)).abs() < 1.0e-12);
+    assert!(res.p_value > 0.01 && res.p_value < 1.0);
+}
+
+#[test]
+fn test_find_equation_fits() {
+    let inputs: [f64; 5] = [1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|r| std::f64::consts::PI * r * r + 0.01).collect();
+
+    let fits = find_equation_fits(&[DISTANCE_UNIT], &[AREA_UNIT], &inputs, &outputs, &[], &FitOptions::default());
+
+    let eq_index = get_equation_by_typeid(figure::circle::CircleArea::params).unwrap();
+    assert_eq!(fits[0].0, eq_index);
+    assert!(fits.windows(2).all(|w| w[0].1.reduced_chi2 <= w[1].1.reduced_chi2));
+    assert!(fits[0].1.constants.is_empty());
+    assert!(fits[0].1.covariance.is_empty());
+}
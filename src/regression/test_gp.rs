@@ -0,0 +1,114 @@
+use crate::*;
+use rustamath_mks::*;
+use super::gp::repair_units;
+
+fn single_thread() -> FitOptions {
+    FitOptions { pool: PoolOptions { threads: Some(1), ..PoolOptions::default() }, ..FitOptions::default() }
+}
+
+#[test]
+fn test_equation_formulas() {
+    for (id, eq) in EQUATIONS.iter().enumerate() {
+        let Some(text) = eq.expr else { continue };
+        let expr: Expr = text.parse().unwrap_or_else(|e| panic!("#{} {}: {}", id, text, e));
+        assert_eq!(expr.to_string(), text);
+        let (out, _cns, inp) = (eq.params)();
+        assert_eq!(out.len(), 1);
+        assert!(expr.has_input());
+        // Panics if formula refers to input the equation does not have
+        assert!(expr.eval(&vec![1.0; inp.len()], &vec![1.0; expr.nr_constants()]).is_finite());
+    }
+}
+
+#[test]
+fn test_repair_units() {
+    let repaired = |text: &str, inputs: &[MksUnit], output: MksUnit| {
+        let mut e = repair_units(text.parse().unwrap(), inputs, output);
+        e.renumber_constants(&mut 0);
+        e.to_string()
+    };
+    assert_eq!(repaired("x0 + x1", &[TIME_UNIT, DISTANCE_UNIT], TIME_UNIT), "x0 + c0*x1");
+    assert_eq!(repaired("sin(x0)", &[TIME_UNIT], SCALAR_UNIT), "sin(c0*x0)");
+    assert_eq!(repaired("x0", &[TIME_UNIT], DISTANCE_UNIT), "c0*x0");
+    assert_eq!(repaired("sqrt(x0)", &[TIME_UNIT], SCALAR_UNIT), "sqrt(c0*x0)");
+    // Consistent trees are left alone
+    assert_eq!(repaired("c0 + c1*x0", &[TIME_UNIT], VELOCITY_UNIT), "c0 + c1*x0");
+    assert_eq!(repaired("sqrt(x0*x0)", &[TIME_UNIT], TIME_UNIT), "sqrt(x0*x0)");
+}
+
+#[test]
+fn test_subtree() {
+    let mut e: Expr = "c0 + sin(x0)".parse().unwrap();
+    let nodes: Vec<String> = (0..e.size()).map(|i| e.subtree(i).unwrap().to_string()).collect();
+    assert_eq!(nodes, ["c0 + sin(x0)", "c0", "sin(x0)", "x0"]);
+    assert!(e.subtree(e.size()).is_none());
+    assert!(e.subtree_mut(e.size()).is_none());
+    *e.subtree_mut(3).unwrap() = Expr::Input(1);
+    assert_eq!(e.to_string(), "c0 + sin(x1)");
+}
+
+#[test]
+fn test_evolve_without_inputs() {
+    let gp = GpOptions { generations: 1, ..GpOptions::default() };
+    let res = super::gp::evolve(&[], &[SCALAR_UNIT], &[], &[1.0, 2.0], &[], &gp, &RegressionConfig::default());
+    assert_eq!(res.err(), Some(Error::DataLength { nr_inputs: 0, nr_outputs: 1, inputs_len: 0, outputs_len: 2 }));
+}
+
+#[test]
+fn test_seeded_population() {
+    // Kinetic energy `E = m*v^2/2` with m = 3 kg
+    let inputs: Vec<f64> = (1..10).map(|i| i as f64 * 0.5).collect();
+    let outputs: Vec<f64> = inputs.iter().map(|v| 1.5 * v * v).collect();
+
+    let gp = GpOptions { generations: 0, ..GpOptions::default() };
+    let exprs = find_expression_gp(&[VELOCITY_UNIT], &[JOULE_UNIT], &inputs, &outputs, &[], &gp, &RegressionConfig::default());
+
+    assert!(exprs[0].chi2 < 1.0e-9);
+    assert!((exprs[0].expr.eval(&[2.0], &exprs[0].constants) - 6.0).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_evolution() {
+    // Not in `EQUATIONS`
+    let inputs: Vec<f64> = (1..13).flat_map(|i| [i as f64 * 0.5, (i % 4) as f64 + 1.0]).collect();
+    let outputs: Vec<f64> = inputs.chunks(2).map(|x| 2.0 * x[0] / x[1] + x[0]).collect();
+
+    let gp = GpOptions { generations: 30, ..GpOptions::default() };
+    let config = RegressionConfig { seed: 1, fit: single_thread(), criterion: Criterion::Bic, ..RegressionConfig::default() };
+    let exprs = find_expression_gp(&[SCALAR_UNIT, SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &gp, &config);
+
+    for (i, e) in exprs.iter().enumerate() {
+        println!("#{}: fit = {:10.6} {} {:?}", i+1, e.chi2, e.expr, e.constants);
+    }
+    assert!(exprs[0].chi2 < 1.0e-6);
+}
+
+#[test]
+fn test_reproducible() {
+    let inputs: Vec<f64> = (0..12).map(|i| i as f64 * 0.25).collect();
+    let outputs: Vec<f64> = inputs.iter().map(|t| 1.0 + 3.0 * t + 0.1 * (t * 7.0).sin()).collect();
+
+    let gp = GpOptions { generations: 5, ..GpOptions::default() };
+    let config = RegressionConfig { seed: 7, ..RegressionConfig::default() };
+    let threaded = find_expression_gp(&[TIME_UNIT], &[VELOCITY_UNIT], &inputs, &outputs, &[], &gp, &config);
+    let config = RegressionConfig { fit: single_thread(), ..config };
+    let serial = find_expression_gp(&[TIME_UNIT], &[VELOCITY_UNIT], &inputs, &outputs, &[], &gp, &config);
+
+    assert_eq!(threaded.len(), serial.len());
+    for (a, b) in threaded.iter().zip(serial.iter()) {
+        assert_eq!(a.expr, b.expr);
+        assert_eq!(a.constants, b.constants);
+    }
+}
+
+#[test]
+fn test_evaluation_budget() {
+    let inputs: Vec<f64> = (1..8).map(|i| i as f64).collect();
+    let outputs: Vec<f64> = inputs.iter().map(|x| x.exp()).collect();
+
+    let gp = GpOptions { max_results: usize::MAX, ..GpOptions::default() };
+    let config = RegressionConfig { budget: Budget { evaluations: Some(10), ..Budget::default() }, ..RegressionConfig::default() };
+    let exprs = find_expression_gp(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &gp, &config);
+
+    assert!(!exprs.is_empty() && exprs.len() <= 10);
+}
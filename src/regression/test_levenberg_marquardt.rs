@@ -0,0 +1,56 @@
+use crate::*;
+
+fn lm_options() -> FitOptions {
+    FitOptions { method: FitMethod::LevenbergMarquardt, tolerance: 1.0e-9, ..FitOptions::default() }
+}
+
+#[test]
+fn test_lm_distance() {
+    // `s = v0*t + (a*t^2)/2` with v0=2.0 and a=3.0
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|t| 2.0*t + 3.0*t*t/2.0).collect();
+
+    let eq_index = get_equation_by_typeid(
+        mechanics::linear_motion::const_accel::DistanceEquation::params).unwrap();
+
+    let chi2_lm = goodness_of_fit_with(eq_index, &inputs, &outputs, &[], &lm_options());
+    let chi2_simplex = goodness_of_fit(eq_index, &inputs, &outputs, &[]);
+    println!("LM: {:e} simplex: {:e}", chi2_lm, chi2_simplex);
+    assert!(chi2_lm < 1.0e-16);
+    assert!(chi2_lm <= chi2_simplex);
+}
+
+#[test]
+fn test_lm_analytic_jacobian() {
+    use std::rc::Rc;
+    use super::expr::ExprModel;
+
+    // `y = c0*exp(c1*x0)`
+    let expr = Expr::Mul(
+        Box::new(Expr::Const(0)),
+        Box::new(Expr::Exp(Box::new(Expr::Mul(Box::new(Expr::Const(1)), Box::new(Expr::Input(0)))))));
+    let model = ExprModel { expr: Rc::new(expr), nr_inp: 1 };
+
+    let inputs: [f64; 6] = [0.0, 0.4, 0.8, 1.2, 1.6, 2.0];
+    let outputs: Vec<f64> = inputs.iter().map(|x| 2.0 * (-0.8 * x).exp()).collect();
+
+    let res = super::fit_and_score(&model, &inputs, &outputs, &[], &lm_options()).unwrap();
+    assert!((res.constants[0] - 2.0).abs() < 1.0e-6, "{:?}", res.constants);
+    assert!((res.constants[1] + 0.8).abs() < 1.0e-6, "{:?}", res.constants);
+    assert!(res.reduced_chi2 < 1.0e-12);
+}
+
+#[test]
+fn test_lm_max_iterations() {
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|v| 7.0 + 0.5*v*v).collect();
+
+    let eq_index = get_equation_by_typeid(
+        mechanics::linear_motion::const_accel::VelocityByDistEquation::params).unwrap();
+
+    let one_iteration = FitOptions { max_iterations: 1, ..lm_options() };
+    let chi2_one = goodness_of_fit_with(eq_index, &inputs, &outputs, &[], &one_iteration);
+    let chi2 = goodness_of_fit_with(eq_index, &inputs, &outputs, &[], &lm_options());
+    println!("1 iteration: {:e} converged: {:e}", chi2_one, chi2);
+    assert!(chi2 < chi2_one);
+}
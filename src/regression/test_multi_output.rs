@@ -0,0 +1,39 @@
+use crate::*;
+use crate::regression::goodness_of_fit;
+use rustamath_mks::*;
+
+#[test]
+fn test_distance_velocity() {
+    // `s = v0*t + (a*t^2)/2, v = v0 + a*t` with v0=2.0 and a=3.0, slightly noisy
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let noise: [f64; 6] = [0.01, -0.02, 0.015, -0.01, 0.02, -0.015];
+    let mut outputs: Vec<f64> = Vec::new();
+    for (t, e) in inputs.iter().zip(noise.iter()) {
+        outputs.push(2.0*t + 3.0*t*t/2.0 + e);
+        outputs.push(2.0 + 3.0*t - e);
+    }
+
+    let eqs = find_equation(&[TIME_UNIT], &[DISTANCE_UNIT, VELOCITY_UNIT], &inputs, &outputs);
+
+    for (i, eq) in eqs.iter().enumerate() {
+        let equation_info = &EQUATIONS[eq.0];
+        println!("#{}: fit = {:8.4} {}", i+1, eq.1, equation_info.desc);
+    }
+
+    let eq_index = get_equation_by_typeid(
+        mechanics::linear_motion::const_accel::DistanceVelocityEquation::params).unwrap();
+    assert_eq!(eqs.len(), 1);
+    assert_eq!(eq_index, eqs[0].0);
+    assert!(eqs[0].1 < 1.0e-3);
+
+    // Per output sigmas: distance measured 10 times less precisely than velocity.
+    let chi2 = goodness_of_fit(eq_index, &inputs, &outputs, &[0.1, 0.01]);
+    let mut ssigmas: Vec<f64> = Vec::new();
+    for _ in inputs.iter() {
+        ssigmas.push(0.1);
+        ssigmas.push(0.01);
+    }
+    let chi2_per_value = goodness_of_fit(eq_index, &inputs, &outputs, &ssigmas);
+    assert_eq!(chi2, chi2_per_value);
+    assert!(chi2 > eqs[0].1);
+}
@@ -0,0 +1,81 @@
+use crate::*;
+use super::units::constant_scales;
+
+fn sine_data() -> (Vec<f64>, Vec<f64>) {
+    let inputs: Vec<f64> = (0..40).map(|i| 0.15 * i as f64).collect();
+    let outputs: Vec<f64> = inputs.iter().map(|t| 3.0 * (4.0 * t + 0.5).sin() + 1.0).collect();
+    (inputs, outputs)
+}
+
+#[test]
+fn test_multi_start_sine() {
+    let (inputs, outputs) = sine_data();
+    let sine_eq_index = get_equation_by_typeid(function::wave::Sine::params).unwrap();
+
+    // Start from all ones instead of the equation's own estimate
+    let ones = Some(vec![1.0; 4]);
+
+    let options = FitOptions { initial: ones.clone(), ..FitOptions::default() };
+    let single = fit_equation(sine_eq_index, &inputs, &outputs, &[], &options);
+
+    let options = FitOptions {
+        method: FitMethod::LevenbergMarquardt,
+        starts: 32,
+        seed: 7,
+        initial: ones,
+        ..FitOptions::default()
+    };
+    let multi = fit_equation(sine_eq_index, &inputs, &outputs, &[], &options);
+
+    assert!(single.reduced_chi2 > 1.0e-2);
+    assert!(multi.reduced_chi2 < 1.0e-9);
+}
+
+#[test]
+fn test_multi_start_bounds() {
+    let (inputs, outputs) = sine_data();
+    let sine_eq_index = get_equation_by_typeid(function::wave::Sine::params).unwrap();
+
+    let options = FitOptions {
+        starts: 24,
+        tolerance: 1.0e-12,
+        max_iterations: 2000,
+        bounds: Some(vec![(0.0, 5.0), (3.0, 5.0), (0.0, 1.0), (-2.0, 2.0)]),
+        ..FitOptions::default()
+    };
+    let res = fit_equation(sine_eq_index, &inputs, &outputs, &[], &options);
+    assert!(res.reduced_chi2 < 1.0e-6, "{:?}", res.constants);
+
+    // Same seed gives same constants
+    let again = fit_equation(sine_eq_index, &inputs, &outputs, &[], &options);
+    assert_eq!(res.constants, again.constants);
+}
+
+#[test]
+fn test_initial_guess() {
+    let (inputs, outputs) = sine_data();
+    let sine_eq_index = get_equation_by_typeid(function::wave::Sine::params).unwrap();
+
+    let options = FitOptions {
+        method: FitMethod::LevenbergMarquardt,
+        initial: Some(vec![2.8, 3.9, 0.6, 0.8]),
+        ..FitOptions::default()
+    };
+    let res = fit_equation(sine_eq_index, &inputs, &outputs, &[], &options);
+    assert!((res.constants[1] - 4.0).abs() < 1.0e-6);
+}
+
+#[test]
+fn test_constant_scales() {
+    // `v = v0 + a*t`
+    let inputs: [f64; 3] = [2.0, 2.0, 2.0];
+    let outputs: [f64; 3] = [4.0, 4.0, 4.0];
+    let units = mechanics::linear_motion::const_accel::VelocityEquation::params();
+
+    let scales = constant_scales(units, &inputs, &outputs);
+    assert_eq!(scales, vec![4.0, 2.0]);
+
+    // `A = side*side`, no constants
+    let units = figure::rectangle::SquareArea::params();
+    assert!(constant_scales(units, &inputs, &outputs).is_empty());
+}
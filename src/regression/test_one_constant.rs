@@ -0,0 +1,65 @@
+use crate::*;
+use rustamath_mks::*;
+
+#[test]
+fn test_newtons_second_law() {
+    // `F = m*a` with m=2.5
+    let inputs: [f64; 5] = [1.0, 2.0, 4.0, 9.8, 12.0];
+    let noise: [f64; 5] = [0.01, -0.02, 0.0, 0.015, -0.01];
+    let outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(a, e)| 2.5 * a + e).collect();
+
+    let eqs = find_equation(&[ACCEL_UNIT], &[NEWTON_UNIT], &inputs, &outputs);
+
+    let eq_index = get_equation_by_typeid(mechanics::dynamics::ForceEquation::params).unwrap();
+    assert_eq!(eqs.len(), 1);
+    assert_eq!(eq_index, eqs[0].0);
+    assert!(eqs[0].1 < 1.0e-3);
+}
+
+#[test]
+fn test_kinetic_energy() {
+    // `E = (m*v^2)/2` with m=4.0
+    let inputs: [f64; 4] = [1.0, 2.0, 3.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|v| 4.0 * v * v / 2.0).collect();
+
+    let eqs = find_equation(&[VELOCITY_UNIT], &[JOULE_UNIT], &inputs, &outputs);
+
+    let eq_index = get_equation_by_typeid(mechanics::dynamics::KineticEnergyEquation::params).unwrap();
+    assert_eq!(eq_index, eqs[0].0);
+    assert!(eqs[0].1 < 1.0e-12);
+}
+
+#[test]
+fn test_uniform_motion() {
+    // `s = v*t` with v=3.0 and a tiny bit of acceleration
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let outputs: Vec<f64> = inputs.iter().map(|t| 3.0 * t + 0.001 * t * t).collect();
+
+    let eqs = find_equation(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs);
+
+    for (i, eq) in eqs.iter().enumerate() {
+        let equation_info = &EQUATIONS[eq.0];
+        println!("#{}: fit = {:10.6} {}", i+1, eq.1, equation_info.desc);
+    }
+
+    let eq_index = get_equation_by_typeid(
+        mechanics::linear_motion::const_velocity::DistanceEquation::params).unwrap();
+    assert!(eqs.iter().any(|eq| eq.0 == eq_index && eq.1 < 1.0e-3));
+}
+
+#[test]
+fn test_nonlinear_one_constant() {
+    use std::rc::Rc;
+    use super::expr::ExprModel;
+
+    // `y = exp(c0*x0)` is not linear in c0, Brent's search is used
+    let expr = Expr::Exp(Box::new(Expr::Mul(Box::new(Expr::Const(0)), Box::new(Expr::Input(0)))));
+    let model = ExprModel { expr: Rc::new(expr), nr_inp: 1 };
+
+    let inputs: [f64; 5] = [0.0, 0.5, 1.0, 1.5, 2.0];
+    let outputs: Vec<f64> = inputs.iter().map(|x| (0.7 * x).exp()).collect();
+
+    let res = super::fit_and_score(&model, &inputs, &outputs, &[], &FitOptions::default()).unwrap();
+    assert!((res.constants[0] - 0.7).abs() < 1.0e-5, "{:?}", res.constants);
+    assert!(res.reduced_chi2 < 1.0e-9);
+}
@@ -0,0 +1,45 @@
+use crate::*;
+use rustamath_mks::*;
+use super::pareto::pareto_front;
+
+fn point(id: usize, complexity: usize, chi2: f64) -> ParetoPoint {
+    ParetoPoint { candidate: Candidate::Equation(id), constants: Vec::new(), chi2, reduced_chi2: chi2, complexity }
+}
+
+#[test]
+fn test_pareto_front() {
+    let points = vec![
+        point(0, 5, 2.0),
+        point(1, 1, 10.0),
+        point(2, 3, 12.0), // dominated by simpler #1
+        point(3, 3, 4.0),
+        point(4, 5, 1.0),
+        point(5, 9, 1.0),  // not better than simpler #4
+        point(6, 7, f64::NAN),
+    ];
+
+    let ids: Vec<Candidate> = pareto_front(points).into_iter().map(|p| p.candidate).collect();
+    assert_eq!(ids, vec![Candidate::Equation(1), Candidate::Equation(3), Candidate::Equation(4)]);
+}
+
+#[test]
+fn test_find_pareto_front() {
+    // `s = v0*t + (a*t^2)/2` with v0=1.0, a=2.0 and measurement noise
+    let inputs: [f64; 8] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+    let noise: [f64; 8] = [0.02, -0.03, 0.01, 0.04, -0.02, 0.0, -0.01, 0.03];
+    let outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(t, e)| t + t * t + e).collect();
+
+    let front = find_pareto_front(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &[0.05], &ExprSearch::default());
+
+    assert!(front.len() > 1);
+    assert!(front.windows(2).all(|w| w[0].complexity < w[1].complexity && w[0].chi2 > w[1].chi2));
+
+    // The knee is the law itself, good fit with the fewest nodes
+    let knee = front.iter().find(|p| p.reduced_chi2 < 2.0).unwrap();
+    let eq_index = get_equation_by_typeid(mechanics::linear_motion::const_accel::DistanceEquation::params).unwrap();
+    match &knee.candidate {
+        Candidate::Equation(id) => assert_eq!(*id, eq_index),
+        Candidate::Expression(expr) => assert_eq!(expr.nr_constants(), 2, "{}", expr),
+    }
+    assert!((knee.constants[1] - 2.0).abs() < 0.05, "{:?}", knee.constants);
+}
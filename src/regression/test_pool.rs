@@ -0,0 +1,73 @@
+use crate::*;
+use rustamath_mks::*;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use super::pool;
+
+#[test]
+fn test_pool_map() {
+    let items: Vec<u64> = (0..100).collect();
+
+    for threads in [None, Some(1), Some(3), Some(1000)] {
+        let options = PoolOptions { threads, ..PoolOptions::default() };
+        // Uneven work, first items are slow so idle workers steal
+        let squares = pool::map(&items, &options, |x| {
+            std::hint::black_box((0..(100 - x) * 1000).sum::<u64>());
+            x * x
+        });
+        assert_eq!(squares, items.iter().map(|x| x * x).collect::<Vec<u64>>());
+    }
+
+    let empty: Vec<u64> = Vec::new();
+    assert!(pool::map(&empty, &PoolOptions::default(), |x| *x).is_empty());
+}
+
+#[test]
+fn test_pool_progress_and_cancel() {
+    let items: Vec<usize> = (0..20).collect();
+
+    let calls = Arc::new(Mutex::new(Vec::new()));
+    let progress_calls = calls.clone();
+    let options = PoolOptions {
+        threads: Some(4),
+        progress: Some(Arc::new(move |done, total| progress_calls.lock().unwrap().push((done, total)))),
+        ..PoolOptions::default()
+    };
+    assert_eq!(pool::map(&items, &options, |x| *x).len(), 20);
+    let mut calls = calls.lock().unwrap().clone();
+    calls.sort();
+    assert_eq!(calls, (1..=20).map(|done| (done, 20)).collect::<Vec<(usize, usize)>>());
+
+    // Cancel after 5 tasks
+    let cancel = Arc::new(AtomicBool::new(false));
+    let cancel_after = cancel.clone();
+    let options = PoolOptions {
+        threads: Some(1),
+        cancel: Some(cancel.clone()),
+        progress: Some(Arc::new(move |done, _| if done == 5 { cancel_after.store(true, Ordering::Relaxed) })),
+    };
+    let done = pool::map(&items, &options, |x| *x);
+    assert_eq!(done, vec![0, 1, 2, 3, 4]);
+
+    // Cancelled before start
+    assert!(pool::map(&items, &options, |x| *x).is_empty());
+}
+
+#[test]
+fn test_find_equation_with_pool() {
+    let inputs = [0.0, 1.0, 2.0, 3.0];
+    let outputs: Vec<f64> = inputs.iter().map(|t| 2.0 * t + 1.5 * t * t).collect();
+
+    let nr_fits = Arc::new(AtomicUsize::new(0));
+    let counter = nr_fits.clone();
+    let pool = PoolOptions {
+        threads: Some(2),
+        progress: Some(Arc::new(move |_, _| { counter.fetch_add(1, Ordering::Relaxed); })),
+        ..PoolOptions::default()
+    };
+    let options = FitOptions { pool, ..FitOptions::default() };
+
+    let eqs = find_equation_with(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &options);
+    assert_eq!(eqs, find_equation(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs));
+    assert_eq!(nr_fits.load(Ordering::Relaxed), eqs.len());
+}
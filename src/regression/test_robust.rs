@@ -0,0 +1,67 @@
+use crate::*;
+
+#[test]
+fn test_loss() {
+    assert_eq!(Loss::Chi2.deviance(3.0), 9.0);
+    assert_eq!(Loss::Chi2.weight(3.0), 1.0);
+
+    assert_eq!(Loss::Huber(1.0).deviance(0.5), 0.25);
+    assert_eq!(Loss::Huber(1.0).deviance(3.0), 5.0);
+    assert_eq!(Loss::Huber(1.0).weight(4.0), 0.25);
+
+    assert!((Loss::Cauchy(1.0).deviance(1.0) - 2.0_f64.ln()).abs() < 1.0e-15);
+    assert_eq!(Loss::Cauchy(1.0).weight(1.0), 0.5);
+
+    assert_eq!(Loss::L1.deviance(-2.0), 4.0);
+    assert_eq!(Loss::L1.weight(-2.0), 0.5);
+}
+
+#[test]
+fn test_robust_fit_with_outlier() {
+    use mechanics::linear_motion::const_accel::VelocityEquation;
+
+    // `v = v0 + a*t` with v0=3.0, a=2.0, one glitch
+    let inputs: Vec<f64> = (0..10).map(|i| i as f64).collect();
+    let noise: [f64; 10] = [0.05, -0.08, 0.01, -0.06, 0.07, 0.06, -0.1, 0.02, 0.09, -0.04];
+    let mut outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(t, e)| 3.0 + 2.0 * t + e).collect();
+    outputs[6] += 25.0;
+
+    let eq_index = get_equation_by_typeid(VelocityEquation::params).unwrap();
+
+    let plain = fit_equation(eq_index, &inputs, &outputs, &[0.1], &FitOptions::default());
+    assert!((plain.constants[1] - 2.0).abs() > 0.1);
+    assert!(plain.down_weighted.is_empty());
+
+    for method in [FitMethod::Simplex, FitMethod::LevenbergMarquardt] {
+        for loss in [Loss::Huber(1.5), Loss::Cauchy(2.0), Loss::L1] {
+            let options = FitOptions { method, loss, tolerance: 1.0e-9, max_iterations: 500, ..FitOptions::default() };
+            let res = fit_equation(eq_index, &inputs, &outputs, &[0.1], &options);
+            assert!((res.constants[0] - 3.0).abs() < 0.1, "{:?} {:?} {:?}", method, loss, res.constants);
+            assert!((res.constants[1] - 2.0).abs() < 0.02, "{:?} {:?} {:?}", method, loss, res.constants);
+            assert!(res.down_weighted.contains(&6), "{:?} {:?}", loss, res.down_weighted);
+            assert_eq!(res.robust_weights.len(), outputs.len());
+            assert!(res.chi2 < plain.chi2);
+        }
+    }
+
+    // Huber reports only the glitch
+    let options = FitOptions { loss: Loss::Huber(1.5), ..FitOptions::default() };
+    let res = fit_equation(eq_index, &inputs, &outputs, &[0.1], &options);
+    assert_eq!(res.down_weighted, vec![6]);
+}
+
+#[test]
+fn test_robust_one_constant() {
+    use mechanics::dynamics::ForceEquation;
+
+    // `F = m*a` with m=2.5, one glitch
+    let inputs: [f64; 6] = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+    let outputs: [f64; 6] = [2.5, 5.0, 7.5, 40.0, 12.5, 15.0];
+
+    let eq_index = get_equation_by_typeid(ForceEquation::params).unwrap();
+    let options = FitOptions { loss: Loss::Cauchy(1.0), ..FitOptions::default() };
+    let res = fit_equation(eq_index, &inputs, &outputs, &[0.1], &options);
+
+    assert!((res.constants[0] - 2.5).abs() < 1.0e-3, "{:?}", res.constants);
+    assert_eq!(res.down_weighted, vec![3]);
+}
@@ -0,0 +1,79 @@
+use crate::*;
+use rustamath_mks::*;
+
+fn single_thread() -> FitOptions {
+    FitOptions { pool: PoolOptions { threads: Some(1), ..PoolOptions::default() }, ..FitOptions::default() }
+}
+
+fn bits(eqs: &[(usize, FitResult)]) -> Vec<(usize, Vec<u64>, u64)> {
+    eqs.iter().map(|(id, res)| (*id, res.constants.iter().map(|c| c.to_bits()).collect(), res.chi2.to_bits())).collect()
+}
+
+#[test]
+fn test_seeded_regression_is_reproducible() {
+    let inputs: Vec<f64> = (0..30).map(|i| 0.2 * i as f64).collect();
+    let noise: Vec<f64> = (0..30).map(|i| 0.01 * ((i * 7) % 5) as f64 - 0.02).collect();
+    let outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(t, e)| 2.0 * (3.0 * t + 0.3).sin() - 0.5 + e).collect();
+
+    let config = |seed: u64, threads: Option<usize>| RegressionConfig {
+        seed,
+        fit: FitOptions {
+            starts: 6,
+            initial: Some(vec![1.0; 4]),
+            pool: PoolOptions { threads, ..PoolOptions::default() },
+            ..FitOptions::default()
+        },
+        ..RegressionConfig::default()
+    };
+
+    let reference = find_equation_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &config(11, Some(1)));
+    for threads in [Some(1), Some(2), Some(8), None] {
+        for _ in 0..3 {
+            let eqs = find_equation_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &config(11, threads));
+            assert_eq!(bits(&eqs), bits(&reference));
+        }
+    }
+
+    // Other seed explores other starting points
+    let other = find_equation_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &config(12, None));
+    assert_ne!(bits(&other), bits(&reference));
+}
+
+#[test]
+fn test_config_streams() {
+    let config = RegressionConfig { seed: 5, ..RegressionConfig::default() };
+    assert_ne!(config.fit_options(0).seed, config.fit_options(1).seed);
+    assert_eq!(config.validation(ValidationMode::KFold(3)).seed, config.validation(ValidationMode::HoldOut(0.2)).seed);
+    let other = RegressionConfig { seed: 6, ..RegressionConfig::default() };
+    assert_ne!(config.fit_options(0).seed, other.fit_options(0).seed);
+}
+
+#[test]
+fn test_seeded_expressions_and_validation() {
+    let inputs: Vec<f64> = (1..10).map(|i| 0.5 * i as f64).collect();
+    let noise: Vec<f64> = (0..9).map(|i| 0.01 * ((i * 3) % 4) as f64 - 0.015).collect();
+    let outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(x, e)| 1.5 * x * x + e).collect();
+
+    let config = |seed: u64| RegressionConfig { seed, fit: FitOptions { starts: 3, ..single_thread() }, ..RegressionConfig::default() };
+
+    let search = ExprSearch { max_size: 3, ..ExprSearch::default() };
+    let exprs = find_expression_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &search, &config(3)).unwrap();
+    let again = find_expression_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &search, &config(3)).unwrap();
+    assert_eq!(exprs.iter().map(|e| &e.constants).collect::<Vec<_>>(), again.iter().map(|e| &e.constants).collect::<Vec<_>>());
+
+    let search = ExhaustiveSearch { max_depth: 2, ..ExhaustiveSearch::default() };
+    let exprs = find_expression_exhaustive_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &search, &config(3)).unwrap();
+    let again = find_expression_exhaustive_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], &search, &config(3)).unwrap();
+    assert_eq!(exprs.iter().map(|e| &e.constants).collect::<Vec<_>>(), again.iter().map(|e| &e.constants).collect::<Vec<_>>());
+
+    // Validation split and fitting options come from the configuration
+    let id = find_equation_by_units(&[SCALAR_UNIT], &[SCALAR_UNIT])[0];
+    let mode = ValidationMode::KFold(3);
+    let score = validate_equation_seeded(id, &inputs, &outputs, &[], mode, &config(3));
+    let explicit = validate_equation(id, &inputs, &outputs, &[], &config(3).fit_options(id), &config(3).validation(mode));
+    assert_eq!(score, explicit);
+
+    let eqs = find_equation_validated_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], mode, &config(3));
+    assert_eq!(eqs, find_equation_validated_seeded(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs, &[], mode, &config(3)));
+    assert!(eqs.windows(2).all(|w| w[0].1.validation_error <= w[1].1.validation_error));
+}
@@ -19,4 +19,34 @@ fn test_sine() {
 
     let sine_eq_index = get_equation_by_typeid(function::wave::Sine::params).unwrap();
     assert_eq!(sine_eq_index, eqs[0].0);
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_sine_guess() {
+    use crate::*;
+    use rustamath_mks::*;
+
+    let inputs: Vec<f64> = (0..40).map(|i| 0.15 * i as f64).collect();
+    let outputs: Vec<f64> = inputs.iter().map(|t| 3.0 * (4.0 * t + 0.5).sin() + 1.0).collect();
+
+    let sine_eq_index = get_equation_by_typeid(function::wave::Sine::params).unwrap();
+    let guess = (EQUATIONS[sine_eq_index].guess)(&inputs, &outputs).unwrap();
+    assert!((guess[1] - 4.0).abs() < 0.2, "{:?}", guess);
+
+    let eqs = find_equation(&[SCALAR_UNIT], &[SCALAR_UNIT], &inputs, &outputs);
+    assert_eq!(sine_eq_index, eqs[0].0);
+    assert!(eqs[0].1 < 1.0e-6);
+}
+
+#[test]
+fn test_sawtooth_guess() {
+    use crate::*;
+
+    let inputs: Vec<f64> = (0..60).map(|i| 0.1 * i as f64).collect();
+    let mut sawtooth = function::wave::Sawtooth::new(0.5, 3.0, 0.2, -1.0);
+    let outputs: Vec<f64> = inputs.iter().map(|t| sawtooth.run(&[*t])[0]).collect();
+
+    let saw_eq_index = get_equation_by_typeid(function::wave::Sawtooth::params).unwrap();
+    let res = fit_equation(saw_eq_index, &inputs, &outputs, &[], &FitOptions::default());
+    assert!(res.reduced_chi2 < 1.0e-6, "{:?}", res.constants);
+}
@@ -0,0 +1,59 @@
+use crate::*;
+use rustamath_mks::*;
+use super::validate::split;
+
+#[test]
+fn test_split() {
+    let validation = Validation { mode: ValidationMode::KFold(3), seed: 42 };
+    let folds = split(10, &validation);
+    assert_eq!(folds.len(), 3);
+
+    // Every measurement is validated exactly once
+    let mut validated: Vec<usize> = folds.iter().flat_map(|(_, valid)| valid.iter().copied()).collect();
+    validated.sort();
+    assert_eq!(validated, (0..10).collect::<Vec<usize>>());
+    for (train, valid) in folds.iter() {
+        assert_eq!(train.len() + valid.len(), 10);
+        assert!(train.iter().all(|i| !valid.contains(i)));
+    }
+
+    // Same seed, same split; other seed, other split
+    assert_eq!(folds, split(10, &validation));
+    assert_ne!(folds, split(10, &Validation { seed: 7, ..validation.clone() }));
+
+    let folds = split(10, &Validation { mode: ValidationMode::HoldOut(0.3), seed: 1 });
+    assert_eq!(folds.len(), 1);
+    assert_eq!(folds[0].0.len(), 7);
+    assert_eq!(folds[0].1.len(), 3);
+}
+
+#[test]
+fn test_validation_penalizes_overfit() {
+    // `s = v*t` with v=3.0 and measurement noise
+    let inputs: Vec<f64> = (0..12).map(|i| i as f64).collect();
+    let noise: [f64; 12] = [0.05, -0.08, 0.01, -0.06, 0.07, 0.06, -0.1, 0.02, 0.09, -0.04, -0.07, 0.03];
+    let outputs: Vec<f64> = inputs.iter().zip(noise.iter()).map(|(t, e)| 3.0 * t + e).collect();
+
+    let uniform = get_equation_by_typeid(mechanics::linear_motion::const_velocity::DistanceEquation::params).unwrap();
+    let accel = get_equation_by_typeid(mechanics::linear_motion::const_accel::DistanceEquation::params).unwrap();
+
+    // Extra constant of `s = v0*t + (a*t^2)/2` follows noise, fits training data better but validates worse
+    let validation = Validation { mode: ValidationMode::KFold(4), seed: 3 };
+    let uniform_score = validate_equation(uniform, &inputs, &outputs, &[0.1], &FitOptions::default(), &validation);
+    let accel_score = validate_equation(accel, &inputs, &outputs, &[0.1], &FitOptions::default(), &validation);
+    assert!(accel_score.train_error < uniform_score.train_error);
+    assert!(accel_score.validation_error > uniform_score.validation_error);
+
+    for mode in [ValidationMode::KFold(4), ValidationMode::HoldOut(0.25)] {
+        let validation = Validation { mode, seed: 3 };
+        let eqs = find_equation_validated(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs,
+            &[0.1], &FitOptions::default(), &validation);
+
+        assert!(eqs.windows(2).all(|w| w[0].1.validation_error <= w[1].1.validation_error));
+
+        // Reproducible from seed
+        let again = find_equation_validated(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs,
+            &[0.1], &FitOptions::default(), &validation);
+        assert_eq!(eqs, again);
+    }
+}
@@ -0,0 +1,54 @@
+use crate::*;
+use rustamath_mks::*;
+
+#[test]
+fn test_weighted_outlier() {
+    // `s = v0*t + (a*t^2)/2` with v0=2.0, a=3.0, last measurement is unreliable
+    let inputs: [f64; 6] = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0];
+    let mut outputs: Vec<f64> = inputs.iter().map(|t| 2.0 * t + 1.5 * t * t).collect();
+    outputs[5] += 20.0;
+    let ssigmas: [f64; 6] = [0.01, 0.01, 0.01, 0.01, 0.01, 100.0];
+
+    let eq_index = get_equation_by_typeid(mechanics::linear_motion::const_accel::DistanceEquation::params).unwrap();
+
+    for method in [FitMethod::Simplex, FitMethod::LevenbergMarquardt] {
+        let options = FitOptions { method, tolerance: 1.0e-9, max_iterations: 1000, ..FitOptions::default() };
+
+        let weighted = fit_equation(eq_index, &inputs, &outputs, &ssigmas, &options);
+        assert!((weighted.constants[0] - 2.0).abs() < 1.0e-2, "{:?}", weighted.constants);
+        assert!((weighted.constants[1] - 3.0).abs() < 1.0e-2, "{:?}", weighted.constants);
+
+        let unweighted = fit_equation(eq_index, &inputs, &outputs, &[], &options);
+        assert!((unweighted.constants[1] - 3.0).abs() > 0.1, "{:?}", unweighted.constants);
+    }
+}
+
+#[test]
+fn test_weighted_one_constant() {
+    // `F = m*a` with m=2.5, last measurement is unreliable
+    let inputs: [f64; 4] = [1.0, 2.0, 3.0, 4.0];
+    let outputs: [f64; 4] = [2.5, 5.0, 7.5, 20.0];
+    let ssigmas: [f64; 4] = [0.1, 0.1, 0.1, 1.0e3];
+
+    let eq_index = get_equation_by_typeid(mechanics::dynamics::ForceEquation::params).unwrap();
+    let res = fit_equation(eq_index, &inputs, &outputs, &ssigmas, &FitOptions::default());
+
+    assert!((res.constants[0] - 2.5).abs() < 1.0e-4);
+}
+
+#[test]
+fn test_find_equation_weighted() {
+    let inputs: [f64; 5] = [0.0, 1.0, 2.0, 3.0, 4.0];
+    let mut outputs: Vec<f64> = inputs.iter().map(|t| 2.0 * t + 1.5 * t * t).collect();
+    outputs[4] += 5.0;
+    let ssigmas: [f64; 5] = [0.01, 0.01, 0.01, 0.01, 50.0];
+
+    let eqs = find_equation_weighted(&[TIME_UNIT], &[DISTANCE_UNIT], &inputs, &outputs, &ssigmas);
+    let eq_index = get_equation_by_typeid(mechanics::linear_motion::const_accel::DistanceEquation::params).unwrap();
+    assert_eq!(eqs[0].0, eq_index);
+    assert!(eqs[0].1 < 1.0e-2);
+
+    // Same score as goodness_of_fit with same sigmas
+    let chi2 = goodness_of_fit(eq_index, &inputs, &outputs, &ssigmas);
+    assert!((chi2 - eqs[0].1).abs() < 1.0e-9);
+}
@@ -0,0 +1,119 @@
+//! Dimensional algebra helpers for `MksUnit`.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+use rustamath_mks::*;
+
+/// Square root of unit, `None` if any unit power is odd.
+///
+/// # Example
+///
+/// ```text
+/// assert_eq!(unit_sqrt(AREA_UNIT), Some(DISTANCE_UNIT));
+/// assert_eq!(unit_sqrt(DISTANCE_UNIT), None);
+/// ```
+pub fn unit_sqrt(unit: MksUnit) -> Option<MksUnit> {
+    let root = MksVal { val: 1.0, unit }.sqrt().unit;
+    if root * root == unit { Some(root) } else { None }
+}
+
+/// Unit raised to integer power.
+pub fn unit_pow(unit: MksUnit, n: i8) -> MksUnit {
+    MksVal { val: 1.0, unit }.pow(n).unit
+}
+
+/// Largest power of base unit found by `unit_exponents`.
+pub const MAX_EXPONENT: i8 = 8;
+
+/// Powers of meter, kilogram, second and ampere in unit.
+///
+/// `MksUnit` keeps its powers private, they are found by dividing the unit
+/// by products of base unit powers until the quotient is `SCALAR_UNIT`.
+/// `None` if any power is beyond `MAX_EXPONENT`.
+///
+/// # Example
+///
+/// ```text
+/// assert_eq!(unit_exponents(ACCEL_UNIT), Some([1, 0, -2, 0]));
+/// ```
+pub fn unit_exponents(unit: MksUnit) -> Option<[i64; 4]> {
+    let powers = || -MAX_EXPONENT..=MAX_EXPONENT;
+    for m in powers() {
+        for kg in powers() {
+            for s in powers() {
+                for a in powers() {
+                    let base = unit_pow(DISTANCE_UNIT, m) * unit_pow(KILOGRAM_UNIT, kg)
+                        * unit_pow(TIME_UNIT, s) * unit_pow(AMPERE_UNIT, a);
+                    if unit / base == SCALAR_UNIT {
+                        return Some([m.into(), kg.into(), s.into(), a.into()]);
+                    }
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Typical magnitude of every constant estimated from its unit and measured data.
+///
+/// Constant unit is matched against products of integer powers (from -2 to 2)
+/// of output and input units, the simplest product wins and its value is
+/// computed from root-mean-square magnitudes of measured columns.
+/// Scale is 1.0 when no product matches.
+pub fn constant_scales(
+    units: (&[MksUnit], &[MksUnit], &[MksUnit]),
+    inputs: &[f64],
+    outputs: &[f64],
+) -> Vec<f64>
+{
+    const MAX_POWER: i8 = 2;
+    const MAX_COLUMNS: usize = 4;
+
+    let (out_units, cns_units, inp_units) = units;
+
+    let rms = |values: &[f64], nr_columns: usize, column: usize| {
+        let column_values: Vec<f64> = values.iter().skip(column).step_by(nr_columns).copied().collect();
+        let sum: f64 = column_values.iter().map(|v| v * v).sum();
+        let rms = (sum / column_values.len().max(1) as f64).sqrt();
+        if rms.is_finite() && rms > 0.0 { rms } else { 1.0 }
+    };
+
+    let mut columns: Vec<(MksUnit, f64)> = Vec::new();
+    for (j, unit) in out_units.iter().enumerate() {
+        columns.push((*unit, rms(outputs, out_units.len(), j)));
+    }
+    for (j, unit) in inp_units.iter().enumerate() {
+        columns.push((*unit, rms(inputs, inp_units.len(), j)));
+    }
+    columns.truncate(MAX_COLUMNS);
+
+    let nr_combinations = (2 * MAX_POWER as usize + 1).pow(columns.len() as u32);
+
+    cns_units.iter().map(|cns_unit| {
+        // (total power, magnitude) of the simplest matching product
+        let mut best: Option<(i32, f64)> = None;
+        for combination in 0..nr_combinations {
+            let mut code = combination;
+            let mut unit = SCALAR_UNIT;
+            let mut magnitude = 1.0_f64;
+            let mut total_power = 0_i32;
+            for (column_unit, column_rms) in columns.iter() {
+                let power = (code % (2 * MAX_POWER as usize + 1)) as i8 - MAX_POWER;
+                code /= 2 * MAX_POWER as usize + 1;
+                unit = unit * unit_pow(*column_unit, power);
+                magnitude *= column_rms.powi(power.into());
+                total_power += i32::from(power.abs());
+            }
+            let simpler = match best {
+                None => true,
+                Some((best_power, best_magnitude)) => total_power < best_power
+                    || (total_power == best_power && magnitude > best_magnitude),
+            };
+            if unit == *cns_unit && simpler {
+                best = Some((total_power, magnitude));
+            }
+        }
+        best.map_or(1.0, |(_, magnitude)| magnitude)
+    }).collect()
+}
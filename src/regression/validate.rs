@@ -0,0 +1,150 @@
+//! Cross-validation of fitted equations.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! - <https://en.wikipedia.org/wiki/Cross-validation_(statistics)>
+//!
+use super::{check_data, fit, fit_and_score, sigma, FitOptions, Model, Result};
+use super::rng::Rng;
+
+/// How measurements are split into training and validation sets.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ValidationMode {
+    /// Every measurement is validated once by the model fitted to the other `k-1` folds
+    KFold(usize),
+    /// Given fraction of measurements is held out for validation
+    HoldOut(f64),
+}
+
+/// Options of cross-validation.
+///
+/// Measurements are shuffled with `seed` before they are split,
+/// same seed gives same split.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{Validation, ValidationMode};
+/// let validation = Validation { mode: ValidationMode::HoldOut(0.25), ..Validation::default() };
+/// assert_eq!(validation.seed, 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Validation {
+    /// Split mode
+    pub mode: ValidationMode,
+    /// Seed of random split
+    pub seed: u64,
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Validation { mode: ValidationMode::KFold(5), seed: 0 }
+    }
+}
+
+/// Training and validation error of a candidate.
+///
+/// Error is χ² per measured value, `χ²/(N*nr_outputs)`, of the training
+/// or validation measurements, summed over all folds; χ² is robust deviance
+/// when fitting options select robust loss.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValidationScore {
+    /// Error on measurements used to fit constants
+    pub train_error: f64,
+    /// Error on measurements not used to fit constants
+    pub validation_error: f64,
+}
+
+/// Split measurement indices into `(train, validation)` sets.
+pub fn split(nr_measurements: usize, validation: &Validation) -> Vec<(Vec<usize>, Vec<usize>)> {
+    let mut indices: Vec<usize> = (0..nr_measurements).collect();
+    Rng::new(validation.seed).shuffle(&mut indices);
+
+    match validation.mode {
+        ValidationMode::KFold(k) => {
+            let k = k.clamp(2, nr_measurements.max(2));
+            (0..k).map(|fold| {
+                let (mut train, mut valid) = (Vec::new(), Vec::new());
+                for (position, index) in indices.iter().enumerate() {
+                    if position % k == fold { valid.push(*index) } else { train.push(*index) }
+                }
+                (train, valid)
+            }).filter(|(train, valid)| !train.is_empty() && !valid.is_empty()).collect()
+        },
+        ValidationMode::HoldOut(fraction) => {
+            let nr_valid = (fraction * nr_measurements as f64).round() as usize;
+            let nr_valid = nr_valid.clamp(1, nr_measurements.saturating_sub(1).max(1));
+            let valid = indices[..nr_valid].to_vec();
+            let train = indices[nr_valid..].to_vec();
+            if train.is_empty() { Vec::new() } else { vec![(train, valid)] }
+        },
+    }
+}
+
+/// Cross-validate model, constants are fitted on training sets only, fail on malformed data.
+pub fn cross_validate(
+    model: &dyn Model,
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    options: &FitOptions,
+    validation: &Validation
+) -> Result<ValidationScore>
+{
+    let (nr_out_params, _, nr_inp_params) = model.dims();
+    let nr_measurements = check_data(nr_inp_params, nr_out_params, inputs, outputs, ssigmas)?;
+
+    let (mut train_chi2, mut nr_train) = (0.0_f64, 0_usize);
+    let (mut valid_chi2, mut nr_valid) = (0.0_f64, 0_usize);
+
+    for (train, valid) in split(nr_measurements, validation) {
+        let (train_inputs, train_outputs, train_ssigmas) = subset(&train, inputs, outputs, ssigmas, nr_inp_params, nr_out_params);
+        let (valid_inputs, valid_outputs, valid_ssigmas) = subset(&valid, inputs, outputs, ssigmas, nr_inp_params, nr_out_params);
+
+        let res = fit_and_score(model, &train_inputs, &train_outputs, &train_ssigmas, options)?;
+        train_chi2 += res.chi2;
+        nr_train += train_outputs.len();
+
+        let predictions = fit::predict(model, &valid_inputs, &res.constants, valid.len(), nr_inp_params);
+        for (k, (output, predicted)) in valid_outputs.iter().zip(predictions.iter()).enumerate() {
+            let sigma = sigma(&valid_ssigmas, nr_out_params, k);
+            valid_chi2 += options.loss.deviance((output - predicted) / sigma);
+        }
+        nr_valid += valid_outputs.len();
+    }
+
+    Ok(ValidationScore {
+        train_error: train_chi2 / nr_train as f64,
+        validation_error: valid_chi2 / nr_valid as f64,
+    })
+}
+
+/// Inputs, outputs and sigmas of selected measurements.
+fn subset(
+    indices: &[usize],
+    inputs: &[f64],
+    outputs: &[f64],
+    ssigmas: &[f64],
+    nr_inp_params: usize,
+    nr_out_params: usize
+) -> (Vec<f64>, Vec<f64>, Vec<f64>)
+{
+    let mut sub_inputs: Vec<f64> = Vec::with_capacity(indices.len() * nr_inp_params);
+    let mut sub_outputs: Vec<f64> = Vec::with_capacity(indices.len() * nr_out_params);
+    for i in indices {
+        sub_inputs.extend_from_slice(&inputs[i * nr_inp_params..(i + 1) * nr_inp_params]);
+        sub_outputs.extend_from_slice(&outputs[i * nr_out_params..(i + 1) * nr_out_params]);
+    }
+
+    // Sigmas per value follow their measurements, empty or per output sigmas apply to any subset.
+    let sub_ssigmas: Vec<f64> = if ssigmas.len() == outputs.len() && ssigmas.len() != nr_out_params {
+        indices.iter().flat_map(|i| ssigmas[i * nr_out_params..(i + 1) * nr_out_params].iter().copied()).collect()
+    } else {
+        ssigmas.to_vec()
+    };
+
+    (sub_inputs, sub_outputs, sub_ssigmas)
+}
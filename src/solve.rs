@@ -0,0 +1,388 @@
+//! Solve equation for any unknown parameter.
+//!
+//! (c) 2023 Igor Lesik
+//! MIT license
+//!
+//! References:
+//!
+//! 1. William H. Press - Numerical recipes, the art of scientific computing.
+//!    Chapter 9.4 Newton-Raphson method using derivative.
+//!
+//! Closed-form inverse is used when equation provides it. Otherwise roots are
+//! bracketed on a grid around typical magnitude of the unknown, which is made of
+//! known values with units of the unknown, and refined by Newton steps kept inside
+//! the bracket. Every root is checked by running the equation on it.
+use rustamath_mks::MksUnit;
+use super::{constant_jacobian, input_jacobian, pi_groups, Equation, Error, Result, EQUATIONS};
+use super::equations::BuildTuple;
+
+/// Parameter of equation to solve for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unknown {
+    /// Output parameter with index
+    Output(usize),
+    /// Constant parameter with index
+    Constant(usize),
+    /// Input parameter with index
+    Input(usize),
+}
+
+/// Known values of equation parameters, `None` if not known.
+///
+/// Value of the unknown itself is ignored.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Knowns {
+    /// Output values
+    pub out: Vec<Option<f64>>,
+    /// Constant values
+    pub cns: Vec<Option<f64>>,
+    /// Input values
+    pub inp: Vec<Option<f64>>,
+}
+
+/// Options of root finding.
+#[derive(Debug, Clone)]
+pub struct SolveOptions {
+    /// Interval to search roots in; by default `±1e-6..1e6` times typical magnitude of the unknown
+    pub bracket: Option<(f64, f64)>,
+    /// Relative tolerance of root
+    pub tolerance: f64,
+    /// Maximum number of refinement steps per root
+    pub max_iterations: usize,
+}
+
+impl Default for SolveOptions {
+    fn default() -> Self {
+        SolveOptions {
+            bracket: None,
+            tolerance: 1.0e-12,
+            max_iterations: 100,
+        }
+    }
+}
+
+/// Number of grid intervals of explicit bracket.
+const GRID: usize = 256;
+
+/// Grid points per decade of default search interval.
+const PER_DECADE: i32 = 8;
+
+/// Relative residual of known output accepted for a root.
+const RESIDUAL: f64 = 1.0e-6;
+
+/// Find all roots of equation for one unknown parameter.
+///
+/// Every constant and input other than the unknown must be known, and at least
+/// one output unless the unknown is an output. Roots are sorted; empty if there is none.
+///
+/// # Example
+///
+/// ```
+/// use rustamath_physics::{solve_equation, get_equation_by_typeid, EquationMaker, Knowns, SolveOptions, Unknown};
+/// use rustamath_physics::mechanics::linear_motion::const_accel::DistanceEquation;
+/// let id = get_equation_by_typeid(DistanceEquation::params).unwrap();
+/// // Time from distance `s = v0*t + a*t^2/2` with v0 = 2, a = 3
+/// let knowns = Knowns { out: vec![Some(170.0)], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+/// let roots = solve_equation(id, &knowns, Unknown::Input(0), &SolveOptions::default()).unwrap();
+/// assert_eq!(roots.len(), 2);
+/// assert!((roots[1] - 10.0).abs() < 1.0e-9);
+/// // Acceleration from distance and time
+/// let knowns = Knowns { out: vec![Some(170.0)], cns: vec![Some(2.0), None], inp: vec![Some(10.0)] };
+/// let roots = solve_equation(id, &knowns, Unknown::Constant(1), &SolveOptions::default()).unwrap();
+/// assert!((roots[0] - 3.0).abs() < 1.0e-9);
+/// ```
+pub fn solve_equation(id: usize, knowns: &Knowns, unknown: Unknown, options: &SolveOptions) -> Result<Vec<f64>> {
+    let eq = EQUATIONS.get(id).ok_or(Error::NoEquation(id))?;
+    let (out_units, cns_units, inp_units) = (eq.params)();
+
+    if knowns.out.len() != out_units.len() {
+        return Err(Error::OutputLength { expected: out_units.len(), actual: knowns.out.len() });
+    }
+    if knowns.cns.len() != cns_units.len() {
+        return Err(Error::ConstantLength { expected: cns_units.len(), actual: knowns.cns.len() });
+    }
+    if knowns.inp.len() != inp_units.len() {
+        return Err(Error::InputLength { expected: inp_units.len(), actual: knowns.inp.len() });
+    }
+
+    let unit = match unknown {
+        Unknown::Output(k) => out_units.get(k),
+        Unknown::Constant(j) => cns_units.get(j),
+        Unknown::Input(j) => inp_units.get(j),
+    }.copied().ok_or(Error::InvalidUnknown)?;
+
+    // Every constant and input but the unknown must be known
+    let values = |known: &[Option<f64>], is_unknown: &dyn Fn(usize) -> bool| -> Result<Vec<f64>> {
+        known.iter().enumerate()
+            .map(|(j, v)| if is_unknown(j) { Ok(f64::NAN) } else { v.ok_or(Error::Underdetermined) })
+            .collect()
+    };
+    let cns = values(&knowns.cns, &|j| unknown == Unknown::Constant(j))?;
+    let inp = values(&knowns.inp, &|j| unknown == Unknown::Input(j))?;
+
+    if let Unknown::Output(k) = unknown {
+        return Ok(vec![(eq.new)(&cns).run(&inp)[k]]);
+    }
+
+    let out: Vec<f64> = knowns.out.iter().map(|y| y.unwrap_or(f64::NAN)).collect();
+    let Some(output) = out.iter().position(|y| !y.is_nan()) else {
+        return Err(Error::Underdetermined);
+    };
+
+    let known: Vec<(MksUnit, f64)> = out_units.iter().zip(out.iter())
+        .chain(cns_units.iter().zip(cns.iter()))
+        .chain(inp_units.iter().zip(inp.iter()))
+        .filter(|(_, v)| v.is_finite() && **v != 0.0)
+        .map(|(u, v)| (*u, *v))
+        .collect();
+
+    let mut problem = Problem {
+        id, eq, unknown, output,
+        out_scales: out_units.iter().map(|u| typical_magnitude(*u, &known)).collect(),
+        equation: (eq.new)(&cns),
+        out, cns, inp,
+    };
+
+    if let Some(roots) = (eq.inverse)(unknown, &problem.out, &problem.cns, &problem.inp) {
+        let roots = problem.verified(roots);
+        if !roots.is_empty() {
+            return Ok(roots);
+        }
+    }
+
+    let grid: Vec<f64> = match options.bracket {
+        Some((lo, hi)) => (0..=GRID).map(|i| lo + (hi - lo) * i as f64 / GRID as f64).collect(),
+        None => {
+            let scale = typical_magnitude(unit, &known);
+            let positive: Vec<f64> = (-6 * PER_DECADE..=6 * PER_DECADE)
+                .map(|k| scale * 10.0_f64.powf(k as f64 / PER_DECADE as f64))
+                .collect();
+            positive.iter().rev().map(|x| -x).chain(std::iter::once(0.0)).chain(positive.iter().copied()).collect()
+        }
+    };
+
+    let mut roots: Vec<f64> = Vec::new();
+    let mut prev = (grid[0], problem.residual(grid[0]).0);
+    for x in grid.iter().skip(1) {
+        let g = problem.residual(*x).0;
+        if g == 0.0 {
+            roots.push(*x);
+        } else if prev.1 * g < 0.0 {
+            roots.push(refine(|x| problem.residual(x), prev.0, *x, prev.1, options));
+        }
+        prev = (*x, g);
+    }
+
+    Ok(problem.verified(roots))
+}
+
+/// Equation with all known values, one unknown and one known output to solve for.
+struct Problem<'a> {
+    id: usize,
+    eq: &'a BuildTuple<'a>,
+    unknown: Unknown,
+    /// Index of known output roots are searched for
+    output: usize,
+    /// Typical magnitude of every output
+    out_scales: Vec<f64>,
+    /// Equation with known constants, rebuilt for every value of unknown constant
+    equation: Box<dyn Equation>,
+    out: Vec<f64>,
+    cns: Vec<f64>,
+    inp: Vec<f64>,
+}
+
+impl Problem<'_> {
+    /// Constants and inputs with unknown set to `x`.
+    fn with(&self, x: f64) -> (Vec<f64>, Vec<f64>) {
+        let (mut cns, mut inp) = (self.cns.clone(), self.inp.clone());
+        match self.unknown {
+            Unknown::Constant(j) => cns[j] = x,
+            Unknown::Input(j) => inp[j] = x,
+            Unknown::Output(_) => {},
+        }
+        (cns, inp)
+    }
+
+    /// Constants and inputs with unknown set to `x`, equation is rebuilt
+    /// only when the unknown is a constant.
+    fn prepare(&mut self, x: f64) -> (Vec<f64>, Vec<f64>) {
+        let (cns, inp) = self.with(x);
+        if let Unknown::Constant(_) = self.unknown {
+            self.equation = (self.eq.new)(&cns);
+        }
+        (cns, inp)
+    }
+
+    /// Difference of output and its known value, with derivative by the unknown.
+    fn residual(&mut self, x: f64) -> (f64, f64) {
+        let (cns, inp) = self.prepare(x);
+        let k = self.output;
+        let value = self.equation.run(&inp)[k] - self.out[k];
+        let derivative = match self.unknown {
+            Unknown::Constant(j) => self.equation.constant_derivatives(&inp)
+                .unwrap_or_else(|| constant_jacobian(self.id, &cns, &inp))[k * cns.len() + j],
+            Unknown::Input(j) => input_jacobian(self.equation.as_mut(), &inp)[k * inp.len() + j],
+            Unknown::Output(_) => 1.0,
+        };
+        (value, derivative)
+    }
+
+    /// Whether all known outputs are reproduced within tolerance of their magnitudes.
+    fn is_root(&mut self, x: f64) -> bool {
+        let (_, inp) = self.prepare(x);
+        let outputs = self.equation.run(&inp);
+        x.is_finite() && outputs.iter().zip(self.out.iter()).zip(self.out_scales.iter())
+            .filter(|((_, y), _)| !y.is_nan())
+            .all(|((f, y), scale)| (f - y).abs() <= RESIDUAL * y.abs().max(*scale))
+    }
+
+    /// Sorted distinct roots that pass the check.
+    fn verified(&mut self, mut roots: Vec<f64>) -> Vec<f64> {
+        roots.retain(|x| self.is_root(*x));
+        roots.sort_by(f64::total_cmp);
+        roots.dedup_by(|a, b| (*a - *b).abs() <= RESIDUAL * a.abs().max(b.abs()));
+        roots
+    }
+}
+
+/// Typical magnitude of quantity with `unit` made of known values.
+///
+/// Magnitude is `(∏|vᵢ|^-pᵢ)^(1/p)` of the simplest dimensionless group `q^p ∏vᵢ^pᵢ`;
+/// 1 if there is no such group.
+fn typical_magnitude(unit: MksUnit, known: &[(MksUnit, f64)]) -> f64 {
+    let units: Vec<MksUnit> = known.iter().map(|(u, _)| *u).chain(std::iter::once(unit)).collect();
+    let n = known.len();
+
+    let group = pi_groups(&units).into_iter()
+        .filter(|g| g.exponents[n] != 0)
+        .min_by_key(|g| g.exponents.iter().map(|e| e.abs()).sum::<i64>());
+    let Some(group) = group else { return 1.0 };
+
+    let power = group.exponents[n] as f64;
+    let magnitude: f64 = known.iter().zip(group.exponents.iter())
+        .map(|((_, v), p)| v.abs().powf(-*p as f64 / power))
+        .product();
+
+    if magnitude.is_finite() && magnitude > 0.0 { magnitude } else { 1.0 }
+}
+
+/// Root of `f` between `a` and `b` where it changes sign, `f(a) = fa`.
+///
+/// Newton step is taken when it stays inside the bracket, bisection otherwise.
+fn refine<F>(mut f: F, a: f64, b: f64, fa: f64, options: &SolveOptions) -> f64
+where
+    F: FnMut(f64) -> (f64, f64),
+{
+    // Keep `f(lo) < 0 < f(hi)`
+    let (mut lo, mut hi) = if fa < 0.0 { (a, b) } else { (b, a) };
+    let mut x = 0.5 * (lo + hi);
+
+    for _ in 0..options.max_iterations {
+        let (fx, dfx) = f(x);
+        if fx == 0.0 {
+            return x;
+        }
+        if fx < 0.0 { lo = x } else { hi = x }
+
+        let newton = x - fx / dfx;
+        let next = if newton.is_finite() && (newton - lo) * (newton - hi) < 0.0 {
+            newton
+        } else {
+            0.5 * (lo + hi)
+        };
+
+        if (next - x).abs() <= options.tolerance * next.abs() || lo == hi {
+            return next;
+        }
+        x = next;
+    }
+
+    x
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+    use mechanics::linear_motion::const_accel::{DistanceEquation, DistanceVelocityEquation, VelocityByDistEquation};
+    use function::wave::{Sawtooth, Sine};
+
+    fn id(params: fn () -> ParamsUnit) -> usize {
+        get_equation_by_typeid(params).unwrap()
+    }
+
+    #[test]
+    fn test_closed_form() {
+        let options = SolveOptions::default();
+
+        // `s = v0*t + a*t^2/2`, v0 = 2, a = 3, t = 10
+        let knowns = Knowns { out: vec![Some(170.0)], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+        let roots = solve_equation(id(DistanceEquation::params), &knowns, Unknown::Input(0), &options).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] + 34.0 / 3.0).abs() < 1.0e-9);
+        assert!((roots[1] - 10.0).abs() < 1.0e-9);
+
+        // Known velocity leaves one time
+        let knowns = Knowns { out: vec![None, Some(32.0)], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+        let roots = solve_equation(id(DistanceVelocityEquation::params), &knowns, Unknown::Input(0), &options).unwrap();
+        assert_eq!(roots, vec![10.0]);
+
+        // Inconsistent distance and velocity have no root
+        let knowns = Knowns { out: vec![Some(100.0), Some(32.0)], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+        let roots = solve_equation(id(DistanceVelocityEquation::params), &knowns, Unknown::Input(0), &options).unwrap();
+        assert!(roots.is_empty());
+
+        // `v^2 = v0^2 + 2*a*s`, both signs of initial velocity
+        let knowns = Knowns { out: vec![Some(5.0)], cns: vec![None, Some(2.0)], inp: vec![Some(4.0)] };
+        let roots = solve_equation(id(VelocityByDistEquation::params), &knowns, Unknown::Constant(0), &options).unwrap();
+        assert_eq!(roots, vec![-3.0, 3.0]);
+
+        // Output is evaluated
+        let knowns = Knowns { out: vec![None], cns: vec![Some(2.0), Some(3.0)], inp: vec![Some(10.0)] };
+        assert_eq!(solve_equation(id(DistanceEquation::params), &knowns, Unknown::Output(0), &options), Ok(vec![170.0]));
+    }
+
+    #[test]
+    fn test_numeric_roots() {
+        use std::f64::consts::PI;
+
+        // `2*sin(x) = 1` has two roots in one period
+        let options = SolveOptions { bracket: Some((0.0, 2.0 * PI)), ..SolveOptions::default() };
+        let knowns = Knowns { out: vec![Some(1.0)], cns: vec![Some(2.0), Some(1.0), Some(0.0), Some(0.0)], inp: vec![None] };
+        let roots = solve_equation(id(Sine::params), &knowns, Unknown::Input(0), &options).unwrap();
+        assert_eq!(roots.len(), 2);
+        assert!((roots[0] - PI / 6.0).abs() < 1.0e-9);
+        assert!((roots[1] - 5.0 * PI / 6.0).abs() < 1.0e-9);
+
+        // Amplitude from default search interval
+        let knowns = Knowns { out: vec![Some(1.0)], cns: vec![None, Some(1.0), Some(0.0), Some(0.0)], inp: vec![Some(PI / 6.0)] };
+        let roots = solve_equation(id(Sine::params), &knowns, Unknown::Constant(0), &SolveOptions::default()).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert!((roots[0] - 2.0).abs() < 1.0e-9);
+
+        // Jump of sawtooth changes sign but is not a root
+        let knowns = Knowns { out: vec![Some(0.5)], cns: vec![Some(1.0), Some(1.0), Some(0.0), Some(0.0)], inp: vec![None] };
+        let roots = solve_equation(id(Sawtooth::params), &knowns, Unknown::Input(0), &options).unwrap();
+        assert_eq!(roots.len(), 1);
+        assert!((roots[0] - 0.5).abs() < 1.0e-9);
+    }
+
+    #[test]
+    fn test_solve_errors() {
+        let options = SolveOptions::default();
+        let eq = id(DistanceEquation::params);
+
+        let knowns = Knowns { out: vec![Some(170.0)], cns: vec![Some(2.0), None], inp: vec![None] };
+        assert_eq!(solve_equation(eq, &knowns, Unknown::Input(0), &options), Err(Error::Underdetermined));
+
+        let knowns = Knowns { out: vec![None], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+        assert_eq!(solve_equation(eq, &knowns, Unknown::Input(0), &options), Err(Error::Underdetermined));
+
+        let knowns = Knowns { out: vec![Some(170.0)], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+        assert_eq!(solve_equation(eq, &knowns, Unknown::Input(1), &options), Err(Error::InvalidUnknown));
+        assert_eq!(solve_equation(99, &knowns, Unknown::Input(0), &options), Err(Error::NoEquation(99)));
+
+        let knowns = Knowns { out: vec![], cns: vec![Some(2.0), Some(3.0)], inp: vec![None] };
+        assert_eq!(solve_equation(eq, &knowns, Unknown::Input(0), &options), Err(Error::OutputLength { expected: 1, actual: 0 }));
+    }
+}